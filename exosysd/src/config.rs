@@ -0,0 +1,354 @@
+//! Configuration of exosysd
+//!
+//! Settings are read from a TOML file, command line arguments and environment variables override them. Settings which
+//! are given nowhere keep the defaults below. Secret URIs and passwords are only accepted from the command line or the
+//! environment, so they don't end up in config files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+
+use crate::keys::KeySource;
+use crate::{Args, Decoding, RunnerBackend, Scheme};
+
+/// Key used in development mode if no executor key is given
+const DEV_SURI: &str = "//Alice";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub node: NodeConfig,
+    pub executor: ExecutorConfig,
+    pub jobs: JobsConfig,
+    pub reports: ReportsConfig,
+    pub log: LogConfig,
+}
+
+/// Connection to the node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    /// WebSocket URL of the node
+    pub url: String,
+    /// How events are decoded
+    pub decoding: Decoding,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        NodeConfig {
+            url: String::from("ws://127.0.0.1:9944"),
+            decoding: Decoding::Dynamic,
+        }
+    }
+}
+
+/// Executor account, which signs the reports
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutorConfig {
+    #[serde(skip)]
+    pub suri: Option<String>,
+    /// File with the secret phrase or seed of the key
+    pub key_file: Option<PathBuf>,
+    /// Keystore directory of a node, which holds the key as single key of `key_type`
+    pub keystore: Option<PathBuf>,
+    pub key_type: String,
+    pub scheme: Scheme,
+    /// Whether the well-known development keys are accepted
+    pub dev: bool,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        ExecutorConfig {
+            suri: None,
+            key_file: None,
+            keystore: None,
+            key_type: String::from("acco"),
+            scheme: Scheme::Sr25519,
+            dev: false,
+        }
+    }
+}
+
+impl ExecutorConfig {
+    /// Where the executor key is loaded from, only one source may be given
+    pub fn key_source(&self) -> Result<KeySource> {
+        let mut sources = Vec::new();
+        if let Some(suri) = &self.suri {
+            sources.push(KeySource::Suri(suri.clone()));
+        }
+        if let Some(key_file) = &self.key_file {
+            sources.push(KeySource::File(key_file.clone()));
+        }
+        if let Some(keystore) = &self.keystore {
+            sources.push(KeySource::Keystore {
+                dir: keystore.clone(),
+                key_type: self.key_type.clone(),
+            });
+        }
+
+        match sources.len() {
+            0 if self.dev => Ok(KeySource::Suri(String::from(DEV_SURI))),
+            0 => bail!("No executor key, give one with --suri, --key-file or --keystore"),
+            1 => Ok(sources.remove(0)),
+            _ => bail!("Only one of --suri, --key-file and --keystore may be given"),
+        }
+    }
+}
+
+/// Execution of the tools
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    /// Directory in which the packages are unpacked, one directory per package hash
+    pub work_dir: PathBuf,
+    /// File in which the last processed block and the running jobs are stored
+    pub state_file: PathBuf,
+    pub runner: RunnerBackend,
+    /// Docker image of the tool container
+    pub tool_image: String,
+    /// Path of the exotool script used by the script runner
+    pub exotool: PathBuf,
+    /// Number of jobs which run at the same time
+    pub workers: usize,
+    /// Seconds after which a job is aborted
+    pub job_timeout: u64,
+    /// Number of retries of jobs which failed or timed out
+    pub max_retries: u32,
+    /// Seconds before the first retry of a job
+    pub retry_backoff: u64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        JobsConfig {
+            work_dir: PathBuf::from("exosysd-jobs"),
+            state_file: PathBuf::from("exosysd-state.json"),
+            runner: RunnerBackend::Native,
+            tool_image: String::from("exotools"),
+            exotool: PathBuf::from("../../../exotools/exotool.sh"),
+            workers: 2,
+            job_timeout: 1800,
+            max_retries: 3,
+            retry_backoff: 30,
+        }
+    }
+}
+
+/// Output of the tool reports
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportsConfig {
+    /// Directory to which the reports are copied, one directory per package hash. Reports stay in the job directories
+    /// if it is not set.
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// One of `off`, `error`, `warn`, `info`, `debug` and `trace`
+    pub level: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            level: String::from("info"),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file at `path`, the defaults are used if there is none
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        match path {
+            Some(path) => {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("Config file {} can't be read", path.display()))?;
+                toml::from_str(&content)
+                    .with_context(|| format!("Invalid config file {}", path.display()))
+            }
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Overrides the settings given on the command line or in the environment
+    pub fn merge_args(&mut self, args: &Args) {
+        fn set<T: Clone>(setting: &mut T, arg: &Option<T>) {
+            if let Some(arg) = arg {
+                *setting = arg.clone();
+            }
+        }
+
+        set(&mut self.node.url, &args.url);
+        set(&mut self.node.decoding, &args.decoding);
+        if args.suri.is_some() {
+            self.executor.suri = args.suri.clone();
+        }
+        if args.key_file.is_some() {
+            self.executor.key_file = args.key_file.clone();
+        }
+        if args.keystore.is_some() {
+            self.executor.keystore = args.keystore.clone();
+        }
+        set(&mut self.executor.key_type, &args.key_type);
+        set(&mut self.executor.scheme, &args.scheme);
+        self.executor.dev |= args.dev;
+        set(&mut self.jobs.work_dir, &args.work_dir);
+        set(&mut self.jobs.state_file, &args.state_file);
+        set(&mut self.jobs.runner, &args.runner);
+        set(&mut self.jobs.tool_image, &args.tool_image);
+        set(&mut self.jobs.exotool, &args.exotool);
+        set(&mut self.jobs.workers, &args.workers);
+        set(&mut self.jobs.job_timeout, &args.job_timeout);
+        set(&mut self.jobs.max_retries, &args.max_retries);
+        set(&mut self.jobs.retry_backoff, &args.retry_backoff);
+        if args.report_dir.is_some() {
+            self.reports.dir = args.report_dir.clone();
+        }
+        set(&mut self.log.level, &args.log_level);
+    }
+
+    /// Checks the settings which would otherwise only fail once they are used
+    pub fn validate(&self) -> Result<()> {
+        if !(self.node.url.starts_with("ws://") || self.node.url.starts_with("wss://")) {
+            bail!("Node URL {} is not a ws:// or wss:// URL", self.node.url);
+        }
+        if self.executor.key_type.len() != 4 {
+            bail!(
+                "Key type {} is not 4 characters long",
+                self.executor.key_type
+            );
+        }
+        self.executor.key_source()?;
+        if self.jobs.workers == 0 {
+            bail!("At least one worker is needed");
+        }
+        if self.jobs.job_timeout == 0 {
+            bail!("Job timeout must not be 0");
+        }
+        if self.jobs.runner == RunnerBackend::Native && self.jobs.tool_image.is_empty() {
+            bail!("Tool image must not be empty");
+        }
+        if self.jobs.runner == RunnerBackend::Script && !self.jobs.exotool.is_file() {
+            bail!(
+                "Exotool script {} does not exist",
+                self.jobs.exotool.display()
+            );
+        }
+        self.log_level()?;
+        Ok(())
+    }
+
+    pub fn log_level(&self) -> Result<LevelFilter> {
+        LevelFilter::from_str(&self.log.level)
+            .with_context(|| format!("Unknown log level {}", self.log.level))
+    }
+
+    /// The config in the format of the config file, without the secret URI
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Config can't be serialized")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn args(args: &[&str]) -> Args {
+        Args::parse_from(std::iter::once("qdao-exosysd").chain(args.iter().copied()))
+    }
+
+    fn dev_config() -> Config {
+        let mut config = Config::default();
+        config.executor.dev = true;
+        config
+    }
+
+    #[test]
+    fn file_settings_are_overridden_by_args() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [node]
+            url = "wss://node.example.com:443"
+
+            [jobs]
+            workers = 4
+            runner = "script"
+
+            [log]
+            level = "debug"
+            "#,
+        )
+        .unwrap();
+
+        config.merge_args(&args(&["--workers", "8", "--report-dir", "reports"]));
+
+        assert_eq!(config.node.url, "wss://node.example.com:443");
+        assert_eq!(config.jobs.workers, 8);
+        assert_eq!(config.jobs.runner, RunnerBackend::Script);
+        assert_eq!(config.jobs.job_timeout, JobsConfig::default().job_timeout);
+        assert_eq!(config.reports.dir, Some(PathBuf::from("reports")));
+        assert_eq!(config.log_level().unwrap(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn unknown_settings_are_rejected() {
+        assert!(toml::from_str::<Config>("[jobs]\nworker = 4\n").is_err());
+        assert!(toml::from_str::<Config>("[executor]\nsuri = \"//Alice\"\n").is_err());
+    }
+
+    #[test]
+    fn invalid_settings_are_reported() {
+        assert!(dev_config().validate().is_ok());
+
+        let mut config = dev_config();
+        config.node.url = String::from("http://127.0.0.1:9933");
+        assert!(config.validate().is_err());
+
+        let mut config = dev_config();
+        config.jobs.workers = 0;
+        assert!(config.validate().is_err());
+
+        let mut config = dev_config();
+        config.log.level = String::from("verbose");
+        assert!(config.validate().is_err());
+
+        let mut config = dev_config();
+        config.executor.suri = Some(String::from("//Bob"));
+        config.executor.keystore = Some(PathBuf::from("keystore"));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn executor_key_is_required_outside_of_dev_mode() {
+        assert!(Config::default().validate().is_err());
+        assert_eq!(
+            dev_config().executor.key_source().unwrap(),
+            KeySource::Suri(String::from(DEV_SURI))
+        );
+    }
+
+    #[test]
+    fn printed_config_can_be_read_back() {
+        let mut config = dev_config();
+        config.executor.suri = Some(String::from("//Bob"));
+        config.reports.dir = Some(PathBuf::from("reports"));
+
+        let printed = config.to_toml().unwrap();
+
+        assert!(!printed.contains("//Bob"));
+        let read: Config = toml::from_str(&printed).unwrap();
+        assert_eq!(read.reports, config.reports);
+        assert_eq!(read.jobs, config.jobs);
+        assert_eq!(read.executor.suri, None);
+    }
+}
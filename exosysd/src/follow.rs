@@ -0,0 +1,91 @@
+//! Following finalized blocks in order, without skipping blocks which were finalized together
+
+use anyhow::{anyhow, bail, Context, Result};
+use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use serde_json::value::Value;
+
+/// Yields every finalized block exactly once and in order of the block number. GRANDPA can finalize several blocks
+/// at once and the subscription only reports the last one, so the blocks in between are fetched by number.
+pub struct BlockFollower<'a> {
+    client: &'a WsClient,
+    subscription: Subscription<Value>,
+    next: Option<u64>,
+    finalized: u64,
+}
+
+impl<'a> BlockFollower<'a> {
+    /// Subscribes to finalized heads. Blocks are yielded from `start` on, which allows to catch up with blocks
+    /// finalized while the deamon was down. Without `start`, the first reported head is the first yielded block.
+    pub async fn subscribe(client: &'a WsClient, start: Option<u64>) -> Result<BlockFollower<'a>> {
+        let subscription = client
+            .subscribe(
+                "chain_subscribeFinalizedHeads",
+                rpc_params![],
+                "chain_unsubscribeFinalizedHeads",
+            )
+            .await?;
+
+        Ok(BlockFollower {
+            client,
+            subscription,
+            next: start,
+            finalized: 0,
+        })
+    }
+
+    /// Returns the number and hash of the next finalized block, waits for the next finalized head if necessary
+    pub async fn next_block(&mut self) -> Result<(u64, String)> {
+        loop {
+            if let Some(next) = self.next.filter(|next| *next <= self.finalized) {
+                let hash = block_hash(self.client, next).await?;
+                self.next = Some(next + 1);
+                return Ok((next, hash));
+            }
+
+            let header = self
+                .subscription
+                .next()
+                .await
+                .ok_or_else(|| anyhow!("Finalized heads subscription was closed"))??;
+            let number = header_number(&header)?;
+            self.next.get_or_insert(number);
+            self.finalized = self.finalized.max(number);
+        }
+    }
+}
+
+/// Reads the block number of a header, which is encoded as hex string
+fn header_number(header: &Value) -> Result<u64> {
+    match header.get("number") {
+        Some(Value::String(number)) => {
+            u64::from_str_radix(number.trim_start_matches("0x"), 16).context("Invalid block number")
+        }
+        _ => bail!("Unexpected header format"),
+    }
+}
+
+/// Returns the hash of the block with the given number
+pub async fn block_hash(client: &WsClient, number: u64) -> Result<String> {
+    let block_hash: Value = client
+        .request("chain_getBlockHash", rpc_params![number])
+        .await?;
+    match block_hash {
+        Value::String(hash) => Ok(hash),
+        _ => bail!("Block {} is not available", number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn header_number_is_parsed() {
+        assert_eq!(header_number(&json!({ "number": "0x1a" })).unwrap(), 26);
+        assert!(header_number(&json!({ "number": 26 })).is_err());
+        assert!(header_number(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Execution requests and the data of the requests stored in `ExoSys`
+
+use anyhow::{anyhow, bail, Result};
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use parity_scale_codec::Decode;
+use serde::{Deserialize, Serialize};
+use serde_json::value::Value;
+use sp_core::{blake2_128, twox_128, H256};
+
+use crate::runner::Outcome;
+use crate::{error, unhex};
+
+const EXO_SYS_NAME: &[u8] = b"ExoSys";
+const REVIEW_RECORD_NAME: &[u8] = b"ReviewRecord";
+
+/// An execution request, which is run by the exotool
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    /// Block in which the request was emitted
+    pub block: u64,
+    /// SS58 address of the requestor
+    pub who: String,
+    /// Hash of the reviewed package
+    pub hash: H256,
+    /// Location of the reviewed package
+    pub url: String,
+    /// Deposit of the requestor, requests with higher deposit are run first
+    #[serde(default)]
+    pub stake: u128,
+}
+
+/// A job and the outcome of its run
+#[derive(Debug)]
+pub struct JobResult {
+    pub job: Job,
+    pub outcome: Outcome,
+}
+
+/// Storage key of the `ReviewRecord` entry of `hash`, which is hashed with `Blake2_128Concat`
+fn review_record_key(hash: &H256) -> String {
+    format!(
+        "0x{}{}{}{}",
+        hex::encode(twox_128(EXO_SYS_NAME)),
+        hex::encode(twox_128(REVIEW_RECORD_NAME)),
+        hex::encode(blake2_128(hash.as_bytes())),
+        hex::encode(hash.as_bytes())
+    )
+}
+
+/// Reads the deposit of the request `hash` at `block_hash`, the deposit is the first field of `ReviewData`
+pub async fn fetch_stake(client: &WsClient, hash: &H256, block_hash: &str) -> Result<u128> {
+    let data: Value = client
+        .request(
+            "state_getStorage",
+            rpc_params![&review_record_key(hash), block_hash],
+        )
+        .await?;
+
+    match data {
+        Value::String(hex_data) => {
+            let data = unhex(&hex_data, error::NotHex::Value)
+                .map_err(|e| anyhow!("Invalid review data: {:?}", e))?;
+            Ok(u128::decode(&mut &data[..])?)
+        }
+        Value::Null => bail!("No review record for {:?}", hash),
+        _ => bail!("Unexpected review data format"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_record_key_is_blake2_128_concat() {
+        let hash = H256::repeat_byte(1);
+        let key = review_record_key(&hash);
+
+        assert!(key.starts_with(&format!(
+            "0x{}{}",
+            hex::encode(twox_128(b"ExoSys")),
+            hex::encode(twox_128(b"ReviewRecord"))
+        )));
+        assert!(key.ends_with(&hex::encode(hash.as_bytes())));
+        assert_eq!(key.len(), 2 + 2 * (16 + 16 + 16 + 32));
+    }
+
+    #[test]
+    fn job_without_stake_is_loaded() {
+        let job: Job = serde_json::from_value(serde_json::json!({
+            "block": 1,
+            "who": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            "hash": format!("{:?}", H256::repeat_byte(1)),
+            "url": "https://example.com/package.tar.gz",
+        }))
+        .expect("Job of an older state file could not be loaded");
+
+        assert_eq!(job.stake, 0);
+    }
+}
@@ -0,0 +1,312 @@
+//! Executor key, which signs the reports of exosysd
+//!
+//! The key is given as secret URI, read from a key file or taken from the keystore of a node. Key files use the format
+//! of the Substrate keystore, a JSON string with the secret phrase or seed, and the password is the password of the
+//! secret URI like in the node keystore. Passwords only apply to secret phrases, raw `0x` seeds can't have one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use parity_scale_codec::Encode;
+use sp_core::crypto::{AccountId32, DEV_PHRASE};
+use sp_core::{ed25519, sr25519, Pair};
+
+use crate::Scheme;
+
+/// Accounts derived from the development phrase, which everybody can sign for
+const DEV_ACCOUNTS: &[&str] = &["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Index of `MultiSignature::Ed25519`
+const ED25519_SIGNATURE: u8 = 0;
+/// Index of `MultiSignature::Sr25519`
+const SR25519_SIGNATURE: u8 = 1;
+
+/// Where the executor key is loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    /// Secret URI, like a secret phrase with derivation path
+    Suri(String),
+    /// File with the secret phrase or seed
+    File(PathBuf),
+    /// Keystore directory of a node, which has to hold a single key of `key_type`
+    Keystore { dir: PathBuf, key_type: String },
+}
+
+/// Key pair of the executor account
+pub enum Signer {
+    Sr25519(sr25519::Pair),
+    Ed25519(ed25519::Pair),
+}
+
+impl Signer {
+    /// Loads the key from `source`
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - Password of the secret URI
+    /// * `dev` - Whether the well-known development keys are accepted
+    pub fn load(
+        source: &KeySource,
+        password: Option<&str>,
+        scheme: Scheme,
+        dev: bool,
+    ) -> Result<Signer> {
+        let signer = match source {
+            KeySource::Suri(suri) => Signer::from_suri(suri, password, scheme)?,
+            KeySource::File(path) => Signer::from_suri(&read_key_file(path)?, password, scheme)
+                .with_context(|| format!("Invalid key in {}", path.display()))?,
+            KeySource::Keystore { dir, key_type } => {
+                let (path, public) = keystore_key(dir, key_type)?;
+                let signer = Signer::from_suri(&read_key_file(&path)?, password, scheme)
+                    .with_context(|| format!("Invalid key in {}", path.display()))?;
+                if signer.public() != public {
+                    bail!(
+                        "Key in {} does not match its public key, is the scheme or password wrong?",
+                        path.display()
+                    );
+                }
+                signer
+            }
+        };
+
+        if !dev && signer.is_dev_key() {
+            bail!("Refusing to use a well-known development key, pass --dev to allow it");
+        }
+        Ok(signer)
+    }
+
+    fn from_suri(suri: &str, password: Option<&str>, scheme: Scheme) -> Result<Signer> {
+        // sp-core silently ignores the password of seeds, which would sign with another key than expected
+        if suri.starts_with("0x") && (password.is_some() || suri.contains("///")) {
+            bail!("A password can only be used with a secret phrase, not with a raw seed");
+        }
+        // The error is reported without the secret URI, which must not end up in the logs
+        Ok(match scheme {
+            Scheme::Sr25519 => Signer::Sr25519(
+                sr25519::Pair::from_string(suri, password)
+                    .map_err(|e| anyhow!("Invalid secret URI: {:?}", e))?,
+            ),
+            Scheme::Ed25519 => Signer::Ed25519(
+                ed25519::Pair::from_string(suri, password)
+                    .map_err(|e| anyhow!("Invalid secret URI: {:?}", e))?,
+            ),
+        })
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        match self {
+            Signer::Sr25519(_) => Scheme::Sr25519,
+            Signer::Ed25519(_) => Scheme::Ed25519,
+        }
+    }
+
+    pub fn public(&self) -> [u8; 32] {
+        match self {
+            Signer::Sr25519(pair) => pair.public().0,
+            Signer::Ed25519(pair) => pair.public().0,
+        }
+    }
+
+    /// The account of the key, which is its public key for both schemes
+    pub fn account(&self) -> AccountId32 {
+        AccountId32::new(self.public())
+    }
+
+    /// Signs `message` and returns the encoded `MultiSignature`
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Signer::Sr25519(pair) => (SR25519_SIGNATURE, pair.sign(message).0).encode(),
+            Signer::Ed25519(pair) => (ED25519_SIGNATURE, pair.sign(message).0).encode(),
+        }
+    }
+
+    fn is_dev_key(&self) -> bool {
+        let scheme = self.scheme();
+        std::iter::once(DEV_PHRASE.to_string())
+            .chain(
+                DEV_ACCOUNTS
+                    .iter()
+                    .flat_map(|name| [format!("//{}", name), format!("//{}//stash", name)]),
+            )
+            .filter_map(|suri| Signer::from_suri(&suri, None, scheme).ok())
+            .any(|dev| dev.public() == self.public())
+    }
+}
+
+/// Reads the secret of a key file, which must not be accessible by other users
+fn read_key_file(path: &Path) -> Result<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path)
+            .with_context(|| format!("Key file {} can't be read", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            bail!(
+                "Key file {} is accessible by other users, restrict it with chmod 600",
+                path.display()
+            );
+        }
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Key file {} can't be read", path.display()))?;
+    // The keystore stores a JSON string, plain files with the secret are accepted as well
+    Ok(serde_json::from_str::<String>(&content).unwrap_or_else(|_| content.trim().to_string()))
+}
+
+/// Finds the single key of `key_type` in a node keystore, whose file names are the hex encoded key type followed by
+/// the hex encoded public key
+fn keystore_key(dir: &Path, key_type: &str) -> Result<(PathBuf, [u8; 32])> {
+    if key_type.len() != 4 {
+        bail!("Key type {} is not 4 characters long", key_type);
+    }
+    let prefix = hex::encode(key_type);
+
+    let mut keys = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Keystore {} can't be read", dir.display()))?
+    {
+        let path = entry?.path();
+        let public = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|public| hex::decode(public).ok())
+            .and_then(|public| <[u8; 32]>::try_from(public).ok());
+        if let Some(public) = public {
+            keys.push((path, public));
+        }
+    }
+
+    match keys.len() {
+        1 => Ok(keys.remove(0)),
+        0 => bail!("Keystore {} has no key of type {}", dir.display(), key_type),
+        count => bail!(
+            "Keystore {} has {} keys of type {}, expected one",
+            dir.display(),
+            count,
+            key_type
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_scale_codec::Decode;
+    use sp_core::crypto::ByteArray;
+
+    const SEED: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const PHRASE: &str =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("exosysd-keys-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_key(path: &Path, content: &str, mode: u32) {
+        fs::write(path, content).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+    }
+
+    #[test]
+    fn dev_keys_require_dev_mode() {
+        for suri in ["//Alice", "//Bob//stash", DEV_PHRASE] {
+            for scheme in [Scheme::Sr25519, Scheme::Ed25519] {
+                let source = KeySource::Suri(suri.to_string());
+                assert!(Signer::load(&source, None, scheme, false).is_err());
+                assert!(Signer::load(&source, None, scheme, true).is_ok());
+            }
+        }
+        let source = KeySource::Suri(SEED.to_string());
+        assert!(Signer::load(&source, None, Scheme::Sr25519, false).is_ok());
+    }
+
+    #[test]
+    fn signatures_are_encoded_multi_signatures() {
+        let message = b"report";
+
+        let signer = Signer::from_suri(SEED, None, Scheme::Sr25519).unwrap();
+        let signature = signer.sign(message);
+        assert_eq!(signature[0], SR25519_SIGNATURE);
+        let signature = sr25519::Signature::decode(&mut &signature[1..]).unwrap();
+        let public = sr25519::Public::from_slice(&signer.public()).unwrap();
+        assert!(sr25519::Pair::verify(&signature, message, &public));
+
+        let signer = Signer::from_suri(SEED, None, Scheme::Ed25519).unwrap();
+        let signature = signer.sign(message);
+        assert_eq!(signature[0], ED25519_SIGNATURE);
+        let signature = ed25519::Signature::decode(&mut &signature[1..]).unwrap();
+        let public = ed25519::Public::from_slice(&signer.public()).unwrap();
+        assert!(ed25519::Pair::verify(&signature, message, &public));
+    }
+
+    #[test]
+    fn key_file_is_loaded_with_password() {
+        let dir = temp_dir("file");
+        let path = dir.join("executor.json");
+        write_key(&path, &format!("\"{}\"\n", PHRASE), 0o600);
+
+        let signer = Signer::load(
+            &KeySource::File(path),
+            Some("secret"),
+            Scheme::Sr25519,
+            false,
+        )
+        .unwrap();
+        let expected = Signer::from_suri(PHRASE, Some("secret"), Scheme::Sr25519).unwrap();
+        assert_eq!(signer.public(), expected.public());
+        assert_ne!(
+            signer.public(),
+            Signer::from_suri(PHRASE, None, Scheme::Sr25519)
+                .unwrap()
+                .public()
+        );
+    }
+
+    #[test]
+    fn password_of_seed_is_rejected() {
+        assert!(Signer::from_suri(SEED, Some("secret"), Scheme::Sr25519).is_err());
+        assert!(Signer::from_suri(&format!("{}///secret", SEED), None, Scheme::Ed25519).is_err());
+        assert!(Signer::from_suri(&format!("{}//executor", SEED), None, Scheme::Sr25519).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn readable_key_file_is_rejected() {
+        let dir = temp_dir("readable");
+        let path = dir.join("executor.json");
+        write_key(&path, SEED, 0o644);
+
+        assert!(Signer::load(&KeySource::File(path), None, Scheme::Sr25519, false).is_err());
+    }
+
+    #[test]
+    fn keystore_key_is_verified() {
+        let dir = temp_dir("keystore");
+        let signer = Signer::from_suri(SEED, None, Scheme::Ed25519).unwrap();
+        let name = format!("{}{}", hex::encode("acco"), hex::encode(signer.public()));
+        write_key(&dir.join(name), &format!("\"{}\"", SEED), 0o600);
+        let source = KeySource::Keystore {
+            dir,
+            key_type: String::from("acco"),
+        };
+
+        let loaded = Signer::load(&source, None, Scheme::Ed25519, false).unwrap();
+        assert_eq!(loaded.public(), signer.public());
+        // The key file holds an ed25519 key, so it does not match as sr25519 key
+        assert!(Signer::load(&source, None, Scheme::Sr25519, false).is_err());
+    }
+}
@@ -1,36 +1,182 @@
 #![deny(unused_crate_dependencies)]
 
-use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
-use clap::Parser;
-use frame_metadata::RuntimeMetadata;
+use anyhow::{anyhow, Result};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use frame_metadata::v14::RuntimeMetadataV14;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::{rpc_params, ws_client::WsClientBuilder};
+use jsonrpsee::{
+    rpc_params,
+    ws_client::{WsClient, WsClientBuilder},
+};
 use lazy_static::lazy_static;
-use parity_scale_codec::Decode;
+use log::{error, info, warn};
+use qdao_events::{ExoSysEvent, QdaoEvent};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::value::Value;
-use sp_core::{twox_128, H256};
+use sp_core::twox_128;
+mod config;
 mod error;
+mod follow;
+mod jobs;
+mod keys;
+mod metadata;
+mod profile;
+mod queue;
+mod runner;
+mod state;
+mod submit;
 
-use substrate_parser::{
-    cards::{ParsedData, Sequence},
-    decode_all_as_type,
-};
-
-const MODULE_NAME: &str = "ExoSys";
-const EXECUTION_REQUEST_NAME: &str = "ExecutionRequest";
-const WHO: &str = "who";
-const HASH: &str = "hash";
-const URL: &str = "url";
+/// Delay before a block which could not be processed is retried
+const BLOCK_RETRY_DELAY: Duration = Duration::from_secs(5);
 
 /// QDAO ExoSys deamon
+///
+/// Settings are read from the config file given by `--config`, the options below and their environment variables
+/// override it.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
+    /// TOML config file
+    #[clap(long, env = "EXOSYSD_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Print the effective config in the format of the config file and exit
+    #[clap(long)]
+    pub print_config: bool,
+
+    /// WebSocket URL of the node [default: ws://127.0.0.1:9944]
     // wss connection is indefinitely stuck, because the node does not respond anything when WSS is not configured properly on it.
-    #[clap(short, long, default_value_t = String::from("ws://127.0.0.1:9944"))]
-    pub url: String,
+    #[clap(short, long, env = "EXOSYSD_URL")]
+    pub url: Option<String>,
+
+    /// File in which the last processed block and the running jobs are stored, processing continues from there after a
+    /// restart [default: exosysd-state.json]
+    #[clap(long, env = "EXOSYSD_STATE_FILE")]
+    pub state_file: Option<PathBuf>,
+
+    /// How events are decoded [default: dynamic]
+    #[clap(long, value_enum, env = "EXOSYSD_DECODING")]
+    pub decoding: Option<Decoding>,
+
+    /// How tools are run for execution requests [default: native]
+    #[clap(long, value_enum, env = "EXOSYSD_RUNNER")]
+    pub runner: Option<RunnerBackend>,
+
+    /// Directory in which the packages are unpacked and the reports are stored, one directory per package hash
+    /// [default: exosysd-jobs]
+    #[clap(long, env = "EXOSYSD_WORK_DIR")]
+    pub work_dir: Option<PathBuf>,
+
+    /// Docker image of the tool container [default: exotools]
+    #[clap(long, env = "EXOSYSD_TOOL_IMAGE")]
+    pub tool_image: Option<String>,
+
+    /// Path of the exotool script used by the script runner [default: ../../../exotools/exotool.sh]
+    #[clap(long, env = "EXOSYSD_EXOTOOL")]
+    pub exotool: Option<PathBuf>,
+
+    /// Directory to which the reports are copied, one directory per package hash
+    #[clap(long, env = "EXOSYSD_REPORT_DIR")]
+    pub report_dir: Option<PathBuf>,
+
+    /// Number of jobs which run at the same time, further jobs wait in a queue ordered by stake [default: 2]
+    #[clap(long, env = "EXOSYSD_WORKERS")]
+    pub workers: Option<usize>,
+
+    /// Seconds after which a job is aborted [default: 1800]
+    #[clap(long, env = "EXOSYSD_JOB_TIMEOUT")]
+    pub job_timeout: Option<u64>,
+
+    /// Number of retries of jobs which failed or timed out [default: 3]
+    #[clap(long, env = "EXOSYSD_MAX_RETRIES")]
+    pub max_retries: Option<u32>,
+
+    /// Seconds before the first retry of a job, doubled for every further retry [default: 30]
+    #[clap(long, env = "EXOSYSD_RETRY_BACKOFF")]
+    pub retry_backoff: Option<u64>,
+
+    /// Secret URI of the executor account, which signs the execution reports. Prefer the environment variable, so the
+    /// secret does not show up in the process list
+    #[clap(long, env = "EXOSYSD_SURI", hide_env_values = true)]
+    pub suri: Option<String>,
+
+    /// File with the secret phrase or seed of the executor account, as stored in a Substrate keystore
+    #[clap(long, env = "EXOSYSD_KEY_FILE")]
+    pub key_file: Option<PathBuf>,
+
+    /// Keystore directory of a node, which holds the executor key as single key of `--key-type`
+    #[clap(long, env = "EXOSYSD_KEYSTORE")]
+    pub keystore: Option<PathBuf>,
+
+    /// Key type of the executor key in the node keystore [default: acco]
+    #[clap(long, env = "EXOSYSD_KEY_TYPE")]
+    pub key_type: Option<String>,
+
+    /// Password of the executor key, only secret phrases can have one
+    #[clap(long, env = "EXOSYSD_KEY_PASSWORD", hide_env_values = true)]
+    pub key_password: Option<String>,
+
+    /// Signature scheme of the executor key [default: sr25519]
+    #[clap(long, value_enum, env = "EXOSYSD_SCHEME")]
+    pub scheme: Option<Scheme>,
+
+    /// Development mode, accepts the well-known development keys and uses `//Alice` if no executor key is given
+    #[clap(long)]
+    pub dev: bool,
+
+    /// One of off, error, warn, info, debug and trace [default: info]
+    #[clap(long, env = "EXOSYSD_LOG_LEVEL")]
+    pub log_level: Option<String>,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Event decoding modes
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decoding {
+    /// With the type information of the runtime metadata, works across runtime upgrades which keep the QDAO events
+    Dynamic,
+    /// With the types generated from the runtime metadata, requires exosysd to be rebuilt after runtime upgrades
+    Static,
+}
+
+/// Runner backends
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnerBackend {
+    /// Download, verify and unpack the package in exosysd and run the tool container directly
+    Native,
+    /// Hand the request to the exotool script
+    Script,
+}
+
+/// Signature schemes of the executor key
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    Sr25519,
+    Ed25519,
+}
+
+/// One-off commands, the deamon listens for execution requests if none is given
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Fetch the profile of an auditor and verify it against the profile hash stored on chain
+    VerifyProfile {
+        /// SS58 address of the auditor
+        account: String,
+        /// HTTP gateway used to retrieve profiles with an `ipfs://` locator
+        #[clap(long, default_value_t = String::from("https://ipfs.io/ipfs/"))]
+        ipfs_gateway: String,
+    },
 }
 
 lazy_static! {
@@ -82,181 +228,253 @@ fn address_with_port(str_address: &str) -> String {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    let mut config = config::Config::load(args.config.as_deref())?;
+    config.merge_args(&args);
 
-    let address = address_with_port(&args.url);
-    let client = WsClientBuilder::default().build(&address).await?;
-
-    let mut uptime = 0;
-    let mut last_block = String::new();
-    loop {
-        let params = rpc_params![];
-        let block_hash_data: Value = client.request("chain_getBlockHash", params).await?;
-        let block_hash = if let Value::String(a) = block_hash_data {
-            a
-        } else {
-            println!("Unexpected block hash format.");
-            continue;
-        };
+    if args.print_config {
+        print!("{}", config.to_toml()?);
+        config.validate()?;
+        return Ok(());
+    }
 
-        if last_block == block_hash {
-            continue;
-        } else {
-            last_block = block_hash.clone();
-        }
+    env_logger::Builder::new()
+        .filter_level(config.log_level()?)
+        .init();
 
-        let metadata: Value = client
-            .request("state_getMetadata", rpc_params![&block_hash])
+    if let Some(Command::VerifyProfile {
+        account,
+        ipfs_gateway,
+    }) = args.command
+    {
+        let client = WsClientBuilder::default()
+            .build(&address_with_port(&config.node.url))
             .await?;
+        profile::verify_profile(&client, &account, &ipfs_gateway).await?;
+        return Ok(());
+    }
 
-        let metadata_v14 = if let Value::String(hex_meta) = metadata {
-            let meta = unhex(&hex_meta, error::NotHex::Value).unwrap();
-            if !meta.starts_with(&[109, 101, 116, 97]) {
-                return Err(Box::from("Wrong start"));
+    config.validate()?;
+    let address = address_with_port(&config.node.url);
+    let client = Arc::new(WsClientBuilder::default().build(&address).await?);
+
+    let signer = keys::Signer::load(
+        &config.executor.key_source()?,
+        args.key_password.as_deref(),
+        config.executor.scheme,
+        config.executor.dev,
+    )?;
+    let submitter = Arc::new(submit::Submitter::new(client.clone(), signer));
+    info!("Reporting as executor {}", submitter.account());
+    let (reported_tx, mut reported_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut store = state::Store::load(&config.jobs.state_file)?;
+    let backend = match config.jobs.runner {
+        RunnerBackend::Native => runner::Backend::Native {
+            image: config.jobs.tool_image.clone(),
+        },
+        RunnerBackend::Script => runner::Backend::Script(config.jobs.exotool.clone()),
+    };
+    let runner = Arc::new(runner::Runner::new(
+        backend,
+        &config.jobs.work_dir,
+        config.reports.dir.clone(),
+    ));
+    let (finished_tx, mut finished_rx) = tokio::sync::mpsc::unbounded_channel();
+    let queue = queue::JobQueue::start(
+        queue::QueueConfig {
+            workers: config.jobs.workers,
+            job_timeout: Duration::from_secs(config.jobs.job_timeout),
+            max_retries: config.jobs.max_retries,
+            retry_backoff: Duration::from_secs(config.jobs.retry_backoff),
+        },
+        runner,
+        finished_tx,
+    );
+    for job in store.in_flight() {
+        info!("Restarting job {:?} from block {}", job.hash, job.block);
+        queue.push(job.clone());
+    }
+
+    let start = store.cursor().map(|cursor| cursor + 1);
+    let mut follower = follow::BlockFollower::subscribe(&client, start).await?;
+    let mut metadata = metadata::MetadataCache::default();
+    loop {
+        tokio::select! {
+            block = follower.next_block() => {
+                let processed = match block {
+                    Ok((number, block_hash)) => {
+                        execution_requests(&client, &mut metadata, config.node.decoding, number, &block_hash)
+                            .await
+                            .map(|jobs| (number, block_hash, jobs))
+                            .map_err(|e| (Some(number), e))
+                    }
+                    Err(e) => Err((None, e)),
+                };
+                match processed {
+                    Ok((number, block_hash, jobs)) => {
+                        for job in jobs {
+                            info!("Author with ID {} requested to run exotool on {}", job.who, job.url);
+                            store.start_job(job.clone());
+                            queue.push(job);
+                        }
+                        store.set_cursor(number);
+                        store.save()?;
+                        info!("block {}: {}", number, block_hash);
+                    }
+                    Err((failed, e)) => {
+                        // The block is processed again, starting after the last block which was stored as processed
+                        let start = store.cursor().map(|cursor| cursor + 1).or(failed);
+                        error!("Block could not be processed, retrying: {:#}", e);
+                        tokio::time::sleep(BLOCK_RETRY_DELAY).await;
+                        follower = resubscribe(&client, start).await;
+                    }
+                }
             }
-            match RuntimeMetadata::decode(&mut &meta[4..]) {
-                Ok(RuntimeMetadata::V14(out)) => out,
-                Ok(_) => continue,
-                Err(_) => continue,
+            Some(result) = finished_rx.recv() => {
+                log_result(&result);
+                let report = submit::Report::of(&result);
+                let submitter = submitter.clone();
+                let reported_tx = reported_tx.clone();
+                tokio::spawn(async move {
+                    match submitter.submit(&report).await {
+                        Ok(block_hash) => {
+                            info!("Report of {:?} is in block {}", report.hash(), block_hash);
+                            let _ = reported_tx.send(report.hash());
+                        }
+                        Err(e) => error!("Report of {:?} failed: {:#}", report.hash(), e),
+                    }
+                });
             }
-        } else {
-            continue;
-        };
+            Some(hash) = reported_rx.recv() => {
+                // Jobs are only finished once reported, so unreported jobs are run again after a restart
+                store.finish_job(&hash);
+                store.save()?;
+            }
+        }
+    }
+}
 
-        let events = client
-            .request(
-                "state_getStorage",
-                rpc_params![
-                    &format!(
-                        "0x{}{}",
-                        hex::encode(twox_128(b"System")),
-                        hex::encode(twox_128(b"Events"))
-                    ),
-                    &block_hash
-                ],
-            )
-            .await?;
+fn log_result(result: &jobs::JobResult) {
+    let hash = result.job.hash;
+    match &result.outcome {
+        runner::Outcome::Completed(output) => {
+            info!("Tool for {:?} exited with {:?}", hash, output.exit_code);
+            if let Some(report_dir) = &output.report_dir {
+                info!("Reports of {:?} are in {}", hash, report_dir.display());
+            }
+            if !output.success() {
+                warn!("{}", output.stderr);
+            }
+        }
+        runner::Outcome::Invalid(reason) => {
+            error!("Request {:?} is invalid: {}", hash, reason)
+        }
+        runner::Outcome::Failed(reason) => error!("Request {:?} failed: {}", hash, reason),
+    }
+}
+
+/// Subscribes to finalized heads again from `start`, until the node accepts the subscription
+async fn resubscribe(client: &WsClient, start: Option<u64>) -> follow::BlockFollower<'_> {
+    loop {
+        match follow::BlockFollower::subscribe(client, start).await {
+            Ok(follower) => return follower,
+            Err(e) => {
+                error!("Finalized heads could not be subscribed to: {:#}", e);
+                tokio::time::sleep(BLOCK_RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Returns the execution requests emitted in the given block
+async fn execution_requests(
+    client: &WsClient,
+    metadata: &mut metadata::MetadataCache,
+    decoding: Decoding,
+    block: u64,
+    block_hash: &str,
+) -> Result<Vec<jobs::Job>> {
+    let mut requests = Vec::new();
 
-        //TODO: turn this into separate crate, this is so reusable!
-        for pallet in metadata_v14.pallets.iter() {
-            if let Some(storage) = &pallet.storage {
-                if storage.prefix == "System" {
-                    for entry in storage.entries.iter() {
-                        if entry.name == "Events" {
-                            if let Value::String(ref hex_data) = events {
-                                let data = unhex(hex_data, error::NotHex::Value).unwrap();
-                                let ty_symbol = match entry.ty {
-                                    frame_metadata::v14::StorageEntryType::Plain(a) => a,
-                                    frame_metadata::v14::StorageEntryType::Map {
-                                        hashers: _,
-                                        key: _,
-                                        value,
-                                    } => value,
-                                };
-                                match decode_all_as_type(&ty_symbol, &data, &metadata_v14.types) {
-                                    Ok(data_parsed) => {
-                                        if let ParsedData::SequenceRaw(a) = data_parsed.data {
-                                            for i in a.data {
-                                                if let ParsedData::Composite(b) = i {
-                                                    for j in b {
-                                                        if j.field_name == Some("event".to_string())
-                                                        {
-                                                            if let ParsedData::Variant(c) =
-                                                                j.data.data
-                                                            {
-                                                                if c.variant_name == MODULE_NAME {
-                                                                    for k in c.fields {
-                                                                        if let ParsedData::Variant(
-                                                                            d,
-                                                                        ) = k.data.data
-                                                                        {
-                                                                            if d.variant_name == EXECUTION_REQUEST_NAME {
-                                                                                let mut who: Option<sp_core::crypto::AccountId32> = None;
-                                                                                let mut hash: Option<H256> =
-                                                                                    None;
-                                                                                 let mut url: Option<String> =
-                                                                                    None;
-                                                                                for l in d.fields {
-                                                                                    if let Some(e) =
-                                                                                        l.field_name
-                                                                                    {
-                                                                                        match e.as_str() {
-                                                                                            WHO => if let ParsedData::Id(f) = l.data.data {
-                                                                                                who = Some(f);
-                                                                                            },
-                                                                                            HASH => if let ParsedData::H256(f) = l.data.data {
-                                                                                                hash = Some(f)
-                                                                                            },
-                                                                                            URL => if let ParsedData::Sequence(f) = l.data.data {
-                                                                                                if let Sequence::U8(g) = f.data {
-                                                                                                    if let Ok(url_string) = String::from_utf8(g) {
-                                                                                                        url = Some(url_string);
-                                                                                                    } else {
-                                                                                                        println!("Error! URL is not UTF-8");
-                                                                                                    }
-                                                                                                }
-                                                                                            },
-                                                                                            _ => println!("Warning: unknown field in execution request event"),
-                                                                                        }
-                                                                                    }
-                                                                                }
-                                                                                if let Some(arg_who) = who {
-                                                                                    if let Some(arg_hash) = hash
-                                                                                    {
-                                                                                        if let Some(arg_url) =
-                                                                                            url
-                                                                                        {
-                                                                                            println!(
-                                                                                                "who: {:?}",
-                                                                                                arg_who
-                                                                                            );
-                                                                                            println!(
-                                                                                                "hash: {:?}",
-                                                                                                arg_hash
-                                                                                            );
-                                                                                            println!(
-                                                                                                "url: {:?}",
-                                                                                                arg_url
-                                                                                            );
-                                                                                            let arg_full_hash = format!(
-                                                                                                "{:?}",
-                                                                                                arg_hash
-                                                                                            );
-                                                                                            println!("{:?}", std::process::Command::new("pwd").spawn());
-                                                                                            println!(
-                                                                                                "Author with ID {:?} requested to run exotool: {:?}",
-                                                                                                arg_who,
-                                                                                                std::process::Command::new("../../../exotools/exotool.sh")
-                                                                                                    .args([arg_url, arg_full_hash])
-                                                                                                    .spawn());
-                                                                                        }
-                                                                                    }
-                                                                                }
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => println!("Error: {:?}", e),
-                                }
+    let events = client
+        .request(
+            "state_getStorage",
+            rpc_params![
+                &format!(
+                    "0x{}{}",
+                    hex::encode(twox_128(b"System")),
+                    hex::encode(twox_128(b"Events"))
+                ),
+                block_hash
+            ],
+        )
+        .await?;
+
+    if let Value::String(ref hex_data) = events {
+        let data = unhex(hex_data, error::NotHex::Value)
+            .map_err(|e| anyhow!("Invalid events in block {}: {:?}", block, e))?;
+        let metadata = match decoding {
+            Decoding::Dynamic => metadata.at(client, block_hash).await?,
+            Decoding::Static => None,
+        };
+        for event in decode_block_events(decoding, metadata, &data, block)? {
+            if let QdaoEvent::ExoSys(ExoSysEvent::ExecutionRequest { who, url, hash }) = event {
+                match String::from_utf8(url) {
+                    Ok(url) => {
+                        let stake = match jobs::fetch_stake(client, &hash, block_hash).await {
+                            Ok(stake) => stake,
+                            Err(e) => {
+                                warn!("Stake of {:?} is unknown: {:#}", hash, e);
+                                0
                             }
-                        }
+                        };
+                        requests.push(jobs::Job {
+                            block,
+                            who: who.to_string(),
+                            hash,
+                            url,
+                            stake,
+                        })
                     }
+                    Err(_) => error!("URL is not UTF-8"),
                 }
             }
         }
-        println!("block: {}", block_hash);
-        println!("======= uptime: {} =======", uptime);
-        uptime += 1;
-        /*
-        if uptime > 2 {
+    }
+    Ok(requests)
+}
+
+/// Decodes the events of a block. Events which can't be decoded are an error, so that the block is retried instead of
+/// skipping its execution requests.
+fn decode_block_events(
+    decoding: Decoding,
+    metadata: Option<&RuntimeMetadataV14>,
+    data: &[u8],
+    block: u64,
+) -> Result<Vec<QdaoEvent>> {
+    let events = match decoding {
+        Decoding::Dynamic => {
+            let metadata = metadata.ok_or_else(|| {
+                anyhow!("Runtime of block {} does not provide V14 metadata", block)
+            })?;
+            qdao_events::decode_events(metadata, data)
         }
-        */
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        Decoding::Static => qdao_events::decode_events_static(data),
+    };
+    events.map_err(|e| anyhow!("Events of block {} can't be decoded: {}", block, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undecodable_events_are_an_error() {
+        // An empty list of event records
+        assert!(decode_block_events(Decoding::Static, None, &[0], 1)
+            .unwrap()
+            .is_empty());
+        assert!(decode_block_events(Decoding::Static, None, &[4, 1, 2], 1).is_err());
+        assert!(decode_block_events(Decoding::Dynamic, None, &[0], 1).is_err());
     }
 }
@@ -0,0 +1,97 @@
+//! Runtime metadata, which is only fetched again after a runtime upgrade
+
+use anyhow::{anyhow, bail, Result};
+use frame_metadata::{v14::RuntimeMetadataV14, RuntimeMetadata};
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use log::info;
+use parity_scale_codec::Decode;
+use serde_json::value::Value;
+
+use crate::{error, unhex};
+
+/// Magic number `meta` at the start of encoded metadata
+const METADATA_MAGIC: &[u8] = b"meta";
+
+/// Decoded metadata of the last seen runtime version. The metadata only changes with the runtime, so it is refetched
+/// when the `spec_version` changes instead of for every block.
+#[derive(Default)]
+pub struct MetadataCache {
+    spec_version: Option<u32>,
+    metadata: Option<RuntimeMetadataV14>,
+}
+
+impl MetadataCache {
+    /// Returns the metadata of the runtime of `block_hash`, `None` if the runtime doesn't provide V14 metadata
+    pub async fn at(
+        &mut self,
+        client: &WsClient,
+        block_hash: &str,
+    ) -> Result<Option<&RuntimeMetadataV14>> {
+        let version: Value = client
+            .request("state_getRuntimeVersion", rpc_params![block_hash])
+            .await?;
+        let spec_version = version_number(&version, "specVersion")?;
+
+        if self.spec_version != Some(spec_version) {
+            let metadata: Value = client
+                .request("state_getMetadata", rpc_params![block_hash])
+                .await?;
+            let metadata = match metadata {
+                Value::String(hex_meta) => unhex(&hex_meta, error::NotHex::Value)
+                    .map_err(|e| anyhow!("Invalid metadata: {:?}", e))?,
+                _ => bail!("Unexpected metadata format"),
+            };
+
+            self.metadata = decode_metadata(&metadata)?;
+            self.spec_version = Some(spec_version);
+            info!("Fetched metadata of runtime version {}", spec_version);
+        }
+
+        Ok(self.metadata.as_ref())
+    }
+}
+
+/// Reads a version number like `specVersion` or `transactionVersion` of a runtime version
+pub fn version_number(version: &Value, field: &str) -> Result<u32> {
+    version
+        .get(field)
+        .and_then(Value::as_u64)
+        .and_then(|number| u32::try_from(number).ok())
+        .ok_or_else(|| anyhow!("Unexpected runtime version format"))
+}
+
+/// Decodes metadata prefixed with the magic number, returns `None` for versions other than V14
+fn decode_metadata(data: &[u8]) -> Result<Option<RuntimeMetadataV14>> {
+    match data.strip_prefix(METADATA_MAGIC) {
+        Some(mut metadata) => match RuntimeMetadata::decode(&mut metadata) {
+            Ok(RuntimeMetadata::V14(metadata)) => Ok(Some(metadata)),
+            Ok(_) => Ok(None),
+            Err(e) => bail!("Metadata could not be decoded: {}", e),
+        },
+        None => bail!("Wrong start"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn version_numbers_are_parsed() {
+        let version =
+            json!({ "specName": "node-template", "specVersion": 100, "transactionVersion": 1 });
+        assert_eq!(version_number(&version, "specVersion").unwrap(), 100);
+        assert_eq!(version_number(&version, "transactionVersion").unwrap(), 1);
+        assert!(version_number(&json!({ "specVersion": "100" }), "specVersion").is_err());
+        assert!(version_number(&json!({}), "specVersion").is_err());
+    }
+
+    #[test]
+    fn metadata_without_magic_is_rejected() {
+        assert!(decode_metadata(&[0, 1, 2, 3, 14]).is_err());
+        assert!(decode_metadata(b"meta").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Verification of auditor profiles against the profile hash stored in `AuditModule`
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use parity_scale_codec::Decode;
+use serde_json::value::Value;
+use sp_core::{blake2_128, blake2_256, crypto::AccountId32, twox_128, H256};
+
+use crate::{error, unhex};
+
+const AUDIT_MODULE_NAME: &[u8] = b"AuditModule";
+const AUDITOR_MAP_NAME: &[u8] = b"AuditorMap";
+const IPFS_SCHEME: &str = "ipfs://";
+
+/// The fields of an `AuditorData` entry required for the profile verification
+pub struct AuditorProfile {
+    pub profile_hash: H256,
+    pub profile_locator: Option<String>,
+}
+
+/// Storage key of the `AuditorMap` entry of `who`, which is hashed with `Blake2_128Concat`
+fn auditor_map_key(who: &AccountId32) -> String {
+    let account: &[u8] = who.as_ref();
+    format!(
+        "0x{}{}{}{}",
+        hex::encode(twox_128(AUDIT_MODULE_NAME)),
+        hex::encode(twox_128(AUDITOR_MAP_NAME)),
+        hex::encode(blake2_128(account)),
+        hex::encode(account)
+    )
+}
+
+/// Decodes an `AuditorData` entry. Bounded vectors are encoded like vectors, `Domain` is encoded as a single byte.
+fn decode_auditor_profile(data: &[u8]) -> Result<AuditorProfile> {
+    let input = &mut &data[..];
+    let _score = <Option<u32>>::decode(input)?;
+    let profile_hash = H256::decode(input)?;
+    let _approved_by = <Vec<AccountId32>>::decode(input)?;
+    let _specializations = <Vec<u8>>::decode(input)?;
+    let profile_locator = <Option<Vec<u8>>>::decode(input)?
+        .map(String::from_utf8)
+        .transpose()
+        .context("Profile locator is not UTF-8")?;
+
+    Ok(AuditorProfile {
+        profile_hash,
+        profile_locator,
+    })
+}
+
+/// Reads the profile hash and locator of `who` from the latest block
+pub async fn fetch_auditor_profile(client: &WsClient, who: &AccountId32) -> Result<AuditorProfile> {
+    let data: Value = client
+        .request("state_getStorage", rpc_params![&auditor_map_key(who)])
+        .await?;
+
+    match data {
+        Value::String(hex_data) => {
+            let data = unhex(&hex_data, error::NotHex::Value)
+                .map_err(|e| anyhow!("Invalid auditor data: {:?}", e))?;
+            decode_auditor_profile(&data)
+        }
+        Value::Null => bail!("{} is not signed up as auditor", who),
+        _ => bail!("Unexpected auditor data format"),
+    }
+}
+
+/// Converts a profile locator into an URL which can be fetched via HTTP. IPFS CIDs are resolved through `ipfs_gateway`.
+pub fn resolve_locator(locator: &str, ipfs_gateway: &str) -> String {
+    match locator.strip_prefix(IPFS_SCHEME) {
+        Some(cid) => format!("{}/{}", ipfs_gateway.trim_end_matches('/'), cid),
+        None => locator.to_string(),
+    }
+}
+
+/// Fetches the profile of `account` and checks that its BLAKE2-256 hash matches the profile hash stored on chain
+pub async fn verify_profile(client: &WsClient, account: &str, ipfs_gateway: &str) -> Result<()> {
+    let who = AccountId32::from_str(account).map_err(|e| anyhow!("Invalid account: {}", e))?;
+    let profile = fetch_auditor_profile(client, &who).await?;
+    let locator = profile
+        .profile_locator
+        .ok_or_else(|| anyhow!("{} has no profile locator", who))?;
+
+    let url = resolve_locator(&locator, ipfs_gateway);
+    println!("Fetching profile from {}", url);
+    let document = reqwest::get(&url)
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let document_hash = H256(blake2_256(&document));
+    if document_hash != profile.profile_hash {
+        bail!(
+            "Profile hash mismatch: stored {:?}, fetched {:?}",
+            profile.profile_hash,
+            document_hash
+        );
+    }
+
+    println!("Profile of {} matches hash {:?}", who, document_hash);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_scale_codec::Encode;
+
+    #[test]
+    fn ipfs_locators_are_resolved() {
+        assert_eq!(
+            resolve_locator("ipfs://bafybeigdyrzt", "https://ipfs.io/ipfs/"),
+            "https://ipfs.io/ipfs/bafybeigdyrzt"
+        );
+        assert_eq!(
+            resolve_locator("https://example.com/profile.md", "https://ipfs.io/ipfs"),
+            "https://example.com/profile.md"
+        );
+    }
+
+    #[test]
+    fn auditor_data_is_decoded() {
+        let locator = b"ipfs://bafybeigdyrzt".to_vec();
+        let data = (
+            Some(2000u32),
+            H256::repeat_byte(1),
+            vec![AccountId32::new([4; 32])],
+            vec![0u8, 2],
+            Some(locator),
+        )
+            .encode();
+
+        let profile = decode_auditor_profile(&data).expect("Decoding failed");
+
+        assert_eq!(profile.profile_hash, H256::repeat_byte(1));
+        assert_eq!(
+            profile.profile_locator.as_deref(),
+            Some("ipfs://bafybeigdyrzt")
+        );
+    }
+}
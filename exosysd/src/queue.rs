@@ -0,0 +1,265 @@
+//! Bounded execution of jobs by a fixed number of workers
+//!
+//! Jobs wait in a queue ordered by the stake of the request, so a burst of requests can't exhaust the executor.
+//! Runs which fail for reasons of the executor or time out are retried with exponential backoff.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{info, warn};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Notify;
+
+use crate::jobs::{Job, JobResult};
+use crate::runner::{Outcome, Runner};
+
+/// Limits of the job execution
+#[derive(Clone, Debug)]
+pub struct QueueConfig {
+    /// Number of jobs which run at the same time
+    pub workers: usize,
+    /// Time after which a run is aborted and counted as failed
+    pub job_timeout: Duration,
+    /// Number of retries of failed runs, invalid requests are not retried
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry
+    pub retry_backoff: Duration,
+}
+
+impl QueueConfig {
+    /// Delay before retrying a job which failed `attempt` times
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+/// A waiting job, jobs with higher stake are run first and jobs with the same stake in the order they were queued
+struct Queued {
+    job: Job,
+    /// Number of failed runs
+    attempt: u32,
+    sequence: u64,
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.job
+            .stake
+            .cmp(&other.job.stake)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+/// Queue of jobs processed by the workers spawned in `JobQueue::start`
+pub struct JobQueue {
+    waiting: Mutex<BinaryHeap<Queued>>,
+    notify: Notify,
+    sequence: AtomicU64,
+    running: AtomicUsize,
+}
+
+impl JobQueue {
+    /// Spawns the workers, which send the result of every job to `finished` once it succeeded, is invalid or ran out
+    /// of retries
+    pub fn start(
+        config: QueueConfig,
+        runner: Arc<Runner>,
+        finished: UnboundedSender<JobResult>,
+    ) -> Arc<JobQueue> {
+        let queue = Arc::new(JobQueue {
+            waiting: Mutex::new(BinaryHeap::new()),
+            notify: Notify::new(),
+            sequence: AtomicU64::new(0),
+            running: AtomicUsize::new(0),
+        });
+        for _ in 0..config.workers.max(1) {
+            tokio::spawn(work(
+                queue.clone(),
+                config.clone(),
+                runner.clone(),
+                finished.clone(),
+            ));
+        }
+        queue
+    }
+
+    pub fn push(&self, job: Job) {
+        self.enqueue(job, 0);
+    }
+
+    fn enqueue(&self, job: Job, attempt: u32) {
+        let sequence = self.sequence.fetch_add(1, AtomicOrdering::Relaxed);
+        self.waiting
+            .lock()
+            .expect("queue lock poisoned")
+            .push(Queued {
+                job,
+                attempt,
+                sequence,
+            });
+        self.notify.notify_one();
+        self.log_depth();
+    }
+
+    async fn pop(&self) -> Queued {
+        loop {
+            if let Some(queued) = self.waiting.lock().expect("queue lock poisoned").pop() {
+                return queued;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Number of waiting jobs
+    pub fn depth(&self) -> usize {
+        self.waiting.lock().expect("queue lock poisoned").len()
+    }
+
+    /// Number of jobs which are being run
+    pub fn running(&self) -> usize {
+        self.running.load(AtomicOrdering::Relaxed)
+    }
+
+    fn log_depth(&self) {
+        info!(
+            "Job queue: {} waiting, {} running",
+            self.depth(),
+            self.running()
+        );
+    }
+}
+
+async fn work(
+    queue: Arc<JobQueue>,
+    config: QueueConfig,
+    runner: Arc<Runner>,
+    finished: UnboundedSender<JobResult>,
+) {
+    loop {
+        let Queued { job, attempt, .. } = queue.pop().await;
+        queue.running.fetch_add(1, AtomicOrdering::Relaxed);
+        queue.log_depth();
+
+        let outcome = match tokio::time::timeout(config.job_timeout, runner.run(&job)).await {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                runner.abort(&job).await;
+                Outcome::Failed(format!("Timed out after {:?}", config.job_timeout))
+            }
+        };
+        queue.running.fetch_sub(1, AtomicOrdering::Relaxed);
+
+        match outcome {
+            Outcome::Failed(reason) if attempt < config.max_retries => {
+                let attempt = attempt + 1;
+                let delay = config.backoff(attempt);
+                warn!(
+                    "Run {} of {:?} failed, retrying in {:?}: {}",
+                    attempt, job.hash, delay, reason
+                );
+                let queue = queue.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    queue.enqueue(job, attempt);
+                });
+            }
+            outcome => {
+                let _ = finished.send(JobResult { job, outcome });
+            }
+        }
+        queue.log_depth();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::Backend;
+    use sp_core::H256;
+
+    fn job(byte: u8, stake: u128) -> Job {
+        Job {
+            block: 1,
+            who: String::from("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            hash: H256::repeat_byte(byte),
+            url: String::from("https://example.com/package.tar.gz"),
+            stake,
+        }
+    }
+
+    fn config(workers: usize) -> QueueConfig {
+        QueueConfig {
+            workers,
+            job_timeout: Duration::from_secs(10),
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn jobs_are_ordered_by_stake() {
+        let mut waiting = BinaryHeap::new();
+        for (sequence, (byte, stake)) in
+            [(1, 10), (2, 30), (3, 10), (4, 20)].into_iter().enumerate()
+        {
+            waiting.push(Queued {
+                job: job(byte, stake),
+                attempt: 0,
+                sequence: sequence as u64,
+            });
+        }
+
+        let order: Vec<_> = std::iter::from_fn(|| waiting.pop())
+            .map(|queued| queued.job.hash)
+            .collect();
+        assert_eq!(order, [2, 4, 1, 3].map(H256::repeat_byte).to_vec());
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let config = QueueConfig {
+            retry_backoff: Duration::from_secs(30),
+            ..config(1)
+        };
+        assert_eq!(config.backoff(1), Duration::from_secs(30));
+        assert_eq!(config.backoff(2), Duration::from_secs(60));
+        assert_eq!(config.backoff(3), Duration::from_secs(120));
+    }
+
+    #[tokio::test]
+    async fn failed_jobs_are_reported_after_retries() {
+        let runner = Arc::new(Runner::new(
+            Backend::Script("/nonexistent/exotool.sh".into()),
+            std::env::temp_dir(),
+            None,
+        ));
+        let (finished_tx, mut finished_rx) = tokio::sync::mpsc::unbounded_channel();
+        let queue = JobQueue::start(config(2), runner, finished_tx);
+
+        queue.push(job(1, 10));
+
+        let result = finished_rx.recv().await.expect("Job did not finish");
+        assert_eq!(result.job, job(1, 10));
+        assert!(matches!(result.outcome, Outcome::Failed(_)));
+        assert_eq!(queue.depth(), 0);
+        assert!(finished_rx.try_recv().is_err());
+    }
+}
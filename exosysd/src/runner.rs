@@ -0,0 +1,728 @@
+//! Running a tool on the package of an execution request
+//!
+//! The native backend downloads the package, verifies it against the hash of the request, unpacks it into a job
+//! directory and runs the tool container on it as an unprivileged user without network access, so the tool only uses
+//! the advisory database of its image. The exotool script is kept as alternative backend.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use log::error;
+use reqwest::StatusCode;
+use sp_core::{keccak_256, H256};
+use tar::{Archive, EntryType};
+use tokio::process::Command;
+
+use crate::jobs::Job;
+
+/// Maximum size of a downloaded package
+const MAX_PACKAGE_SIZE: usize = 64 * 1024 * 1024;
+/// Maximum size of the unpacked files of a package
+const MAX_UNPACKED_SIZE: u64 = 512 * 1024 * 1024;
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// Directory of the job inside the tool container
+const CONTAINER_MOUNTPOINT: &str = "/exotools";
+/// User and group `nobody`, which run the tool container if exosysd runs as root
+const UNPRIVILEGED_USER: (u32, u32) = (65534, 65534);
+
+/// Exit code of the audit script if no vulnerabilities were found
+const EXIT_PASSED: i32 = 0;
+/// Exit code of the audit script if vulnerabilities were found
+const EXIT_FINDINGS: i32 = 1;
+/// Exit code of the audit script if the package can't be audited, other exit codes are tool errors
+const EXIT_UNAUDITABLE: i32 = 2;
+
+/// How the tool is run
+#[derive(Clone, Debug)]
+pub enum Backend {
+    /// Natively, in a container of the given image
+    Native { image: String },
+    /// With the exotool script at the given path, which does all steps itself
+    Script(PathBuf),
+}
+
+/// Output of a tool run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolOutput {
+    /// Exit code of the tool, `None` if it was killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Directory with the reports of the tool, unknown for the script backend
+    pub report_dir: Option<PathBuf>,
+}
+
+impl ToolOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Result of a job
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The tool audited the package, its exit code tells whether it found vulnerabilities
+    Completed(ToolOutput),
+    /// The request can't be executed, because the package URL is invalid, the package does not match the hash of the
+    /// request or isn't a safe tarball
+    Invalid(String),
+    /// The tool could not be run on this executor or the package server is unavailable, which is not caused by the
+    /// request
+    Failed(String),
+}
+
+/// Directories of a job, laid out as expected by the audit script of the tool container
+struct JobDirs {
+    mountpoint: PathBuf,
+    download: PathBuf,
+    extract: PathBuf,
+    report: PathBuf,
+    latest_report: PathBuf,
+}
+
+impl JobDirs {
+    fn new(work_dir: &Path, hash: &H256, timestamp: u64) -> JobDirs {
+        let mountpoint = work_dir.join(format!("{:?}", hash));
+        JobDirs {
+            download: mountpoint.join("audit_files").join("download"),
+            extract: mountpoint.join("audit_files").join("extract"),
+            report: mountpoint.join("reports").join(timestamp.to_string()),
+            latest_report: mountpoint.join("latest_report"),
+            mountpoint,
+        }
+    }
+
+    /// Creates the directories of a new attempt. The tool could have replaced files of an earlier attempt with links,
+    /// so the job directory is removed as a whole and every directory is created anew instead of following a path
+    /// which already exists.
+    fn create(&self) -> Result<()> {
+        remove_path(&self.mountpoint)?;
+        for dir in [
+            &self.mountpoint,
+            &self.mountpoint.join("audit_files"),
+            &self.download,
+            &self.extract,
+            &self.mountpoint.join("reports"),
+            &self.report,
+            &self.latest_report,
+        ] {
+            fs::create_dir(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the package to a new file, which fails instead of following a link
+    fn write_package(&self, package: &[u8]) -> Result<()> {
+        let path = self.download.join("package");
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(package))
+            .with_context(|| format!("Cannot write {}", path.display()))
+    }
+}
+
+/// Removes a file, link or directory with its content, without following links
+fn remove_path(path: &Path) -> Result<()> {
+    let removed = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    removed.with_context(|| format!("Cannot remove {}", path.display()))
+}
+
+/// Runs jobs with the configured backend
+pub struct Runner {
+    backend: Backend,
+    work_dir: PathBuf,
+    report_dir: Option<PathBuf>,
+}
+
+impl Runner {
+    /// # Arguments
+    ///
+    /// * `backend` - How the tool is run
+    /// * `work_dir` - Directory in which every job gets a directory named after its hash
+    /// * `report_dir` - Directory to which the reports are copied, they stay in the job directory if it is `None`
+    pub fn new(
+        backend: Backend,
+        work_dir: impl Into<PathBuf>,
+        report_dir: Option<PathBuf>,
+    ) -> Runner {
+        Runner {
+            backend,
+            work_dir: work_dir.into(),
+            report_dir,
+        }
+    }
+
+    pub async fn run(&self, job: &Job) -> Outcome {
+        match &self.backend {
+            Backend::Native { image } => self.run_native(job, image).await,
+            Backend::Script(exotool) => {
+                let output = Command::new(exotool)
+                    .args([job.url.as_str(), &format!("{:?}", job.hash)])
+                    // exosysd submits the report, the logger would report a second time
+                    .env("EXOTOOL_NO_LOGGER", "1")
+                    .kill_on_drop(true)
+                    .output()
+                    .await;
+                match output {
+                    Ok(output) => outcome(ToolOutput {
+                        exit_code: output.status.code(),
+                        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                        report_dir: None,
+                    }),
+                    Err(e) => Outcome::Failed(format!("Exotool could not be started: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Stops the tool container of a job whose run was aborted. The exotool script is killed when its run is
+    /// dropped.
+    pub async fn abort(&self, job: &Job) {
+        if let Backend::Native { .. } = self.backend {
+            let stopped = Command::new("docker")
+                .arg("kill")
+                .arg(container_name(&job.hash))
+                .output()
+                .await;
+            if let Err(e) = stopped {
+                error!("Container of {:?} could not be stopped: {}", job.hash, e);
+            }
+        }
+    }
+
+    async fn run_native(&self, job: &Job, image: &str) -> Outcome {
+        let package = match download(&job.url).await {
+            Ok(package) => package,
+            Err(e) if is_transient(&e) => {
+                return Outcome::Failed(format!("Package could not be downloaded: {:#}", e))
+            }
+            Err(e) => return Outcome::Invalid(format!("Package could not be downloaded: {:#}", e)),
+        };
+        if let Err(e) = verify(&package, &job.hash) {
+            return Outcome::Invalid(format!("{:#}", e));
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        let dirs = JobDirs::new(&self.work_dir, &job.hash, timestamp);
+        let prepared = dirs.create().and_then(|_| dirs.write_package(&package));
+        if let Err(e) = prepared {
+            return Outcome::Failed(format!("Job directory could not be prepared: {:#}", e));
+        }
+
+        let extract = dirs.extract.clone();
+        match tokio::task::spawn_blocking(move || unpack(&package, &extract)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                return Outcome::Invalid(format!("Package could not be unpacked: {:#}", e))
+            }
+            Err(e) => return Outcome::Failed(format!("Unpacking failed: {}", e)),
+        }
+
+        let user = match container_user(&dirs.mountpoint) {
+            Ok(user) => user,
+            Err(e) => {
+                return Outcome::Failed(format!("Job directory could not be prepared: {:#}", e))
+            }
+        };
+
+        match run_container(image, &job.hash, &dirs.mountpoint, user, timestamp).await {
+            Ok(output) => match outcome(output) {
+                Outcome::Completed(output) => Outcome::Completed(ToolOutput {
+                    report_dir: Some(self.publish_reports(job, dirs.report, timestamp)),
+                    ..output
+                }),
+                outcome => outcome,
+            },
+            Err(e) => Outcome::Failed(format!("Tool container could not be run: {:#}", e)),
+        }
+    }
+
+    /// Copies the reports of a run to the report directory, returns where the reports are
+    fn publish_reports(&self, job: &Job, report: PathBuf, timestamp: u64) -> PathBuf {
+        let report_dir = match &self.report_dir {
+            Some(report_dir) => report_dir,
+            None => return report,
+        };
+        let destination = report_dir
+            .join(format!("{:?}", job.hash))
+            .join(timestamp.to_string());
+        match copy_reports(&report, &destination) {
+            Ok(()) => destination,
+            Err(e) => {
+                error!(
+                    "Reports of {:?} could not be copied to {}: {:#}",
+                    job.hash,
+                    destination.display(),
+                    e
+                );
+                report
+            }
+        }
+    }
+}
+
+/// Tells audit results apart from packages which can't be audited and errors of the tool by the exit code of the audit
+/// script, only audit results are reported as such
+fn outcome(output: ToolOutput) -> Outcome {
+    match output.exit_code {
+        Some(EXIT_PASSED | EXIT_FINDINGS) => Outcome::Completed(output),
+        Some(EXIT_UNAUDITABLE) => Outcome::Invalid(format!(
+            "Package can't be audited: {}",
+            output.stdout.trim()
+        )),
+        exit_code => Outcome::Failed(format!(
+            "Tool exited with {:?}: {}",
+            exit_code,
+            output.stderr.trim()
+        )),
+    }
+}
+
+/// Copies the files and directories of `source` to `destination`. The reports are written by the tool, which processes
+/// untrusted packages, so links are skipped instead of being followed.
+fn copy_reports(source: &Path, destination: &Path) -> Result<()> {
+    if fs::symlink_metadata(source)?.file_type().is_symlink() {
+        bail!("Report directory {} is a link", source.display());
+    }
+    fs::create_dir_all(destination)
+        .with_context(|| format!("Cannot create {}", destination.display()))?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = destination.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_reports(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Cannot copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Downloads the package at `url`, packages larger than `MAX_PACKAGE_SIZE` are rejected
+async fn download(url: &str) -> Result<Vec<u8>> {
+    let mut response = reqwest::get(url).await?.error_for_status()?;
+    if response.content_length().unwrap_or_default() > MAX_PACKAGE_SIZE as u64 {
+        bail!("Package is larger than {} bytes", MAX_PACKAGE_SIZE);
+    }
+
+    let mut package = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if package.len() + chunk.len() > MAX_PACKAGE_SIZE {
+            bail!("Package is larger than {} bytes", MAX_PACKAGE_SIZE);
+        }
+        package.extend_from_slice(&chunk);
+    }
+    Ok(package)
+}
+
+/// Whether a download error is caused by the network or the server and may go away on retry. Invalid URLs, client
+/// errors like 404 and oversized packages are caused by the request.
+fn is_transient(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(e) => match e.status() {
+            Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+            None => !(e.is_builder() || e.is_redirect()),
+        },
+        None => false,
+    }
+}
+
+/// Checks that the Keccak-256 hash of the package is the hash of the request
+fn verify(package: &[u8], hash: &H256) -> Result<()> {
+    let package_hash = H256(keccak_256(package));
+    if package_hash != *hash {
+        bail!("Package hash {:?} does not match {:?}", package_hash, hash);
+    }
+    Ok(())
+}
+
+/// Unpacks a tar or gzipped tar package into `destination`
+fn unpack(package: &[u8], destination: &Path) -> Result<()> {
+    if package.starts_with(GZIP_MAGIC) {
+        unpack_archive(Archive::new(GzDecoder::new(package)), destination)
+    } else {
+        unpack_archive(Archive::new(package), destination)
+    }
+}
+
+/// Only regular files and directories with relative paths inside `destination` are unpacked. Links could point
+/// outside of it and device files have no place in a package, so packages containing them are rejected as a whole.
+fn unpack_archive<R: Read>(mut archive: Archive<R>, destination: &Path) -> Result<()> {
+    let mut unpacked_size = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if !is_safe_path(&path) {
+            bail!("Unsafe path {} in package", path.display());
+        }
+
+        match entry.header().entry_type() {
+            EntryType::Regular | EntryType::Directory => {}
+            EntryType::XGlobalHeader => continue,
+            entry_type => bail!(
+                "Unsupported entry {} of type {:?} in package",
+                path.display(),
+                entry_type
+            ),
+        }
+
+        unpacked_size += entry.size();
+        if unpacked_size > MAX_UNPACKED_SIZE {
+            bail!(
+                "Unpacked package is larger than {} bytes",
+                MAX_UNPACKED_SIZE
+            );
+        }
+        entry.unpack_in(destination)?;
+    }
+    Ok(())
+}
+
+/// A path is safe if it is relative and does not leave the directory it is unpacked in
+fn is_safe_path(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn container_name(hash: &H256) -> String {
+    format!("exotool-{:?}", hash)
+}
+
+/// Returns the user and group the tool container runs as. The tool processes untrusted packages, so it runs as the
+/// owner of the job directory, which is the user of exosysd. If that is root, the job directory is handed over to
+/// `UNPRIVILEGED_USER` instead. `None` if users are unknown to the platform.
+fn container_user(mountpoint: &Path) -> Result<Option<(u32, u32)>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = fs::metadata(mountpoint)
+            .with_context(|| format!("Cannot read {}", mountpoint.display()))?;
+        if metadata.uid() != 0 {
+            return Ok(Some((metadata.uid(), metadata.gid())));
+        }
+        chown_tree(mountpoint, UNPRIVILEGED_USER)?;
+        Ok(Some(UNPRIVILEGED_USER))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mountpoint;
+        Ok(None)
+    }
+}
+
+/// Changes the owner of `path` and everything in it, links are changed themselves instead of being followed
+#[cfg(unix)]
+fn chown_tree(path: &Path, (uid, gid): (u32, u32)) -> Result<()> {
+    std::os::unix::fs::lchown(path, Some(uid), Some(gid))
+        .with_context(|| format!("Cannot change the owner of {}", path.display()))?;
+    if fs::symlink_metadata(path)?.is_dir() {
+        for entry in fs::read_dir(path)? {
+            chown_tree(&entry?.path(), (uid, gid))?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs the tool container on the job directory, the container has no network access, runs as `user` and is removed
+/// afterwards
+async fn run_container(
+    image: &str,
+    hash: &H256,
+    mountpoint: &Path,
+    user: Option<(u32, u32)>,
+    timestamp: u64,
+) -> Result<ToolOutput> {
+    let mountpoint = mountpoint
+        .canonicalize()
+        .with_context(|| format!("Cannot resolve {}", mountpoint.display()))?;
+    let name = container_name(hash);
+    let hash = format!("{:?}", hash);
+    let timestamp = timestamp.to_string();
+
+    let mut docker = Command::new("docker");
+    docker
+        .args(["run", "--rm", "--network", "none", "--name"])
+        .arg(name);
+    if let Some((uid, gid)) = user {
+        docker.arg("--user").arg(format!("{}:{}", uid, gid));
+    }
+    let output = docker
+        .arg("--volume")
+        .arg(format!("{}:{}", mountpoint.display(), CONTAINER_MOUNTPOINT))
+        .arg(image)
+        .args([
+            "--hash",
+            &hash,
+            "--date",
+            &timestamp,
+            "--date_readable",
+            &timestamp,
+        ])
+        .output()
+        .await?;
+
+    Ok(ToolOutput {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        report_dir: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tar::{Builder, Header};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("exosysd-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Creating temporary directory failed");
+        dir
+    }
+
+    fn header(path: &str, entry_type: EntryType, size: u64) -> Header {
+        let mut header = Header::new_gnu();
+        // The path is written directly, because `set_path` refuses unsafe paths
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_cksum();
+        header
+    }
+
+    fn package(entries: &[(&str, EntryType, &str)]) -> Vec<u8> {
+        let mut builder = Builder::new(Vec::new());
+        for (path, entry_type, data) in entries {
+            let header = header(path, *entry_type, data.len() as u64);
+            builder
+                .append(&header, data.as_bytes())
+                .expect("Appending entry failed");
+        }
+        builder.into_inner().expect("Building package failed")
+    }
+
+    /// Serves a single HTTP response with the given status and returns the URL of the server
+    async fn serve_status(status: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/package.tar", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn download_errors_are_classified() {
+        let unavailable = download(&serve_status("503 Service Unavailable").await).await;
+        assert!(is_transient(&unavailable.unwrap_err()));
+
+        let not_found = download(&serve_status("404 Not Found").await).await;
+        assert!(!is_transient(&not_found.unwrap_err()));
+
+        // Nothing listens on the port of a dropped listener
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let refused = format!("http://{}/package.tar", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(is_transient(&download(&refused).await.unwrap_err()));
+
+        assert!(!is_transient(&download("not a url").await.unwrap_err()));
+    }
+
+    #[test]
+    fn tool_errors_are_not_audit_results() {
+        let output = |exit_code| ToolOutput {
+            exit_code,
+            stdout: String::new(),
+            stderr: String::new(),
+            report_dir: None,
+        };
+
+        assert!(matches!(outcome(output(Some(0))), Outcome::Completed(_)));
+        assert!(matches!(outcome(output(Some(1))), Outcome::Completed(_)));
+        assert!(matches!(outcome(output(Some(2))), Outcome::Invalid(_)));
+        for exit_code in [Some(3), Some(125), None] {
+            assert!(matches!(outcome(output(exit_code)), Outcome::Failed(_)));
+        }
+    }
+
+    #[test]
+    fn package_is_verified_against_hash() {
+        let package = b"package".to_vec();
+        assert!(verify(&package, &H256(keccak_256(&package))).is_ok());
+        assert!(verify(&package, &H256::repeat_byte(1)).is_err());
+    }
+
+    #[test]
+    fn package_is_unpacked() {
+        let destination = temp_dir("unpack");
+        let package = package(&[
+            ("src", EntryType::Directory, ""),
+            ("src/Cargo.toml", EntryType::Regular, "[package]"),
+        ]);
+
+        unpack(&package, &destination).expect("Unpacking failed");
+
+        assert_eq!(
+            fs::read(destination.join("src/Cargo.toml")).unwrap(),
+            b"[package]"
+        );
+        fs::remove_dir_all(&destination).expect("Removing temporary directory failed");
+    }
+
+    #[test]
+    fn gzipped_package_is_unpacked() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let destination = temp_dir("unpack-gz");
+        let package = package(&[("Cargo.lock", EntryType::Regular, "version = 3")]);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&package).unwrap();
+
+        unpack(&encoder.finish().unwrap(), &destination).expect("Unpacking failed");
+
+        assert_eq!(
+            fs::read(destination.join("Cargo.lock")).unwrap(),
+            b"version = 3"
+        );
+        fs::remove_dir_all(&destination).expect("Removing temporary directory failed");
+    }
+
+    #[test]
+    fn path_traversal_is_rejected() {
+        let destination = temp_dir("traversal");
+        let inner = destination.join("inner");
+
+        for path in ["../escaped", "src/../../escaped", "/tmp/escaped"] {
+            let package = package(&[(path, EntryType::Regular, "evil")]);
+            assert!(unpack(&package, &inner).is_err());
+        }
+
+        assert!(!destination.join("escaped").exists());
+        fs::remove_dir_all(&destination).expect("Removing temporary directory failed");
+    }
+
+    #[test]
+    fn links_are_rejected() {
+        let destination = temp_dir("links");
+
+        for entry_type in [EntryType::Symlink, EntryType::Link] {
+            let package = package(&[("link", entry_type, "")]);
+            assert!(unpack(&package, &destination).is_err());
+        }
+
+        assert!(!destination.join("link").exists());
+        fs::remove_dir_all(&destination).expect("Removing temporary directory failed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn job_dir_of_earlier_attempt_is_not_followed() {
+        use std::os::unix::fs::symlink;
+
+        let work_dir = temp_dir("job-dir");
+        let outside = temp_dir("job-dir-outside");
+        fs::write(outside.join("package"), "outside").unwrap();
+        let dirs = JobDirs::new(&work_dir, &H256::repeat_byte(1), 1);
+        dirs.create().unwrap();
+        // Links planted by the tool during an earlier attempt
+        fs::remove_dir_all(&dirs.download).unwrap();
+        symlink(&outside, &dirs.download).unwrap();
+        symlink(&outside, dirs.extract.join("linked")).unwrap();
+
+        dirs.create().unwrap();
+        dirs.write_package(b"package").unwrap();
+
+        assert_eq!(fs::read(dirs.download.join("package")).unwrap(), b"package");
+        assert!(!dirs.extract.join("linked").exists());
+        assert_eq!(
+            fs::read_to_string(outside.join("package")).unwrap(),
+            "outside"
+        );
+
+        // A link planted at the package is not followed either
+        fs::remove_file(dirs.download.join("package")).unwrap();
+        symlink(outside.join("package"), dirs.download.join("package")).unwrap();
+        assert!(dirs.write_package(b"package").is_err());
+        assert_eq!(
+            fs::read_to_string(outside.join("package")).unwrap(),
+            "outside"
+        );
+
+        // The job directory itself may be a link
+        fs::remove_dir_all(&dirs.mountpoint).unwrap();
+        symlink(&outside, &dirs.mountpoint).unwrap();
+        dirs.create().unwrap();
+        assert!(!fs::symlink_metadata(&dirs.mountpoint)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(outside.join("package").exists());
+
+        fs::remove_dir_all(&work_dir).expect("Removing temporary directory failed");
+        fs::remove_dir_all(&outside).expect("Removing temporary directory failed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn container_does_not_run_as_root() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dirs = JobDirs::new(&temp_dir("user"), &H256::repeat_byte(1), 1);
+        dirs.create().unwrap();
+        dirs.write_package(b"package").unwrap();
+
+        let (uid, gid) = container_user(&dirs.mountpoint).unwrap().unwrap();
+
+        assert_ne!(uid, 0);
+        let package = fs::metadata(dirs.download.join("package")).unwrap();
+        assert_eq!((package.uid(), package.gid()), (uid, gid));
+        fs::remove_dir_all(temp_dir("user")).expect("Removing temporary directory failed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_are_copied_without_links() {
+        let source = temp_dir("reports");
+        fs::create_dir_all(source.join("cargo")).unwrap();
+        fs::write(source.join("cargo").join("report.json"), "{}").unwrap();
+        std::os::unix::fs::symlink("/etc/passwd", source.join("passwd")).unwrap();
+        let destination = temp_dir("published").join("0x01").join("1");
+
+        copy_reports(&source, &destination).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.join("cargo").join("report.json")).unwrap(),
+            "{}"
+        );
+        assert!(!destination.join("passwd").exists());
+        fs::remove_dir_all(&source).expect("Removing temporary directory failed");
+        fs::remove_dir_all(temp_dir("published")).expect("Removing temporary directory failed");
+    }
+}
@@ -0,0 +1,109 @@
+//! Local state of the deamon, which survives restarts
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sp_core::H256;
+
+use crate::jobs::Job;
+
+/// The persisted state
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct State {
+    /// Number of the last block whose execution requests were started
+    cursor: Option<u64>,
+    /// Jobs which were started but did not finish yet
+    in_flight: Vec<Job>,
+}
+
+/// JSON file storing the cursor and the running jobs
+pub struct Store {
+    path: PathBuf,
+    state: State,
+}
+
+impl Store {
+    /// Loads the state from `path`, a missing file is an empty state
+    pub fn load(path: impl Into<PathBuf>) -> Result<Store> {
+        let path = path.into();
+        let state = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("Invalid state file {}", path.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => State::default(),
+            Err(e) => return Err(e).context(format!("Cannot read {}", path.display())),
+        };
+        Ok(Store { path, state })
+    }
+
+    /// Writes the state to a temporary file which replaces the state file, so that a crash does not leave a
+    /// truncated state file behind
+    pub fn save(&self) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&self.state)?)
+            .with_context(|| format!("Cannot write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Cannot replace {}", self.path.display()))
+    }
+
+    /// Number of the last processed block
+    pub fn cursor(&self) -> Option<u64> {
+        self.state.cursor
+    }
+
+    pub fn set_cursor(&mut self, block: u64) {
+        self.state.cursor = Some(block);
+    }
+
+    pub fn in_flight(&self) -> &[Job] {
+        &self.state.in_flight
+    }
+
+    pub fn start_job(&mut self, job: Job) {
+        if !self.state.in_flight.iter().any(|j| j.hash == job.hash) {
+            self.state.in_flight.push(job);
+        }
+    }
+
+    pub fn finish_job(&mut self, hash: &H256) {
+        self.state.in_flight.retain(|job| job.hash != *hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(byte: u8) -> Job {
+        Job {
+            block: byte as u64,
+            who: String::from("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            hash: H256::repeat_byte(byte),
+            url: String::from("https://example.com/package.tar.gz"),
+            stake: 0,
+        }
+    }
+
+    #[test]
+    fn state_survives_restart() {
+        let path = std::env::temp_dir().join(format!("exosysd-state-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut store = Store::load(&path).expect("Loading empty state failed");
+        assert_eq!(store.cursor(), None);
+        store.start_job(job(1));
+        store.start_job(job(2));
+        store.start_job(job(2));
+        store.finish_job(&H256::repeat_byte(1));
+        store.set_cursor(7);
+        store.save().expect("Saving state failed");
+
+        let restored = Store::load(&path).expect("Loading state failed");
+        assert_eq!(restored.cursor(), Some(7));
+        assert_eq!(restored.in_flight(), &[job(2)]);
+
+        fs::remove_file(&path).expect("Removing state file failed");
+    }
+}
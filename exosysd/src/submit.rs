@@ -0,0 +1,415 @@
+//! Signing and submission of the `ExoSys` extrinsics which report the outcome of execution requests
+//!
+//! Extrinsics are built for the signed extensions of the QDAO runtime and are immortal. Nonces are counted locally
+//! and fetched from the node again after a submission failed.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use frame_metadata::v14::RuntimeMetadataV14;
+use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use parity_scale_codec::{Compact, Encode};
+use scale_info::TypeDef;
+use serde_json::value::Value;
+use sp_core::{blake2_256, crypto::AccountId32, H256};
+use tokio::sync::Mutex;
+
+use crate::jobs::JobResult;
+use crate::keys::Signer;
+use crate::metadata::{version_number, MetadataCache};
+use crate::runner::Outcome;
+use crate::{error, follow, unhex};
+
+const EXO_SYS_NAME: &str = "ExoSys";
+const AUTO_REPORT_CALL: &str = "tool_exec_auto_report";
+const CANCEL_INVALID_CALL: &str = "tool_exec_cancel_invalid";
+/// Results reported for tools which exited successfully and which failed
+const SUCCESS_RESULT: &[u8] = b"1";
+const FAILURE_RESULT: &[u8] = b"0";
+
+const EXTRINSIC_VERSION: u8 = 4;
+const SIGNED_FLAG: u8 = 0b1000_0000;
+/// Index of `MultiAddress::Id`
+const ADDRESS_ID: u8 = 0;
+const IMMORTAL_ERA: u8 = 0;
+/// Payloads longer than this are hashed before signing
+const MAX_UNHASHED_PAYLOAD: usize = 256;
+
+/// Report of the outcome of an execution request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Report {
+    /// `tool_exec_auto_report` with the result of the tool
+    Result { hash: H256, result: Vec<u8> },
+    /// `tool_exec_cancel_invalid` for requests which could not be executed
+    Invalid { hash: H256 },
+}
+
+impl Report {
+    /// Report of a finished job, audit results are reported and jobs which could not be run are cancelled
+    pub fn of(result: &JobResult) -> Report {
+        let hash = result.job.hash;
+        match &result.outcome {
+            Outcome::Completed(output) => Report::Result {
+                hash,
+                result: if output.success() {
+                    SUCCESS_RESULT.to_vec()
+                } else {
+                    FAILURE_RESULT.to_vec()
+                },
+            },
+            Outcome::Invalid(_) | Outcome::Failed(_) => Report::Invalid { hash },
+        }
+    }
+
+    pub fn hash(&self) -> H256 {
+        match self {
+            Report::Result { hash, .. } | Report::Invalid { hash } => *hash,
+        }
+    }
+
+    fn call_name(&self) -> &'static str {
+        match self {
+            Report::Result { .. } => AUTO_REPORT_CALL,
+            Report::Invalid { .. } => CANCEL_INVALID_CALL,
+        }
+    }
+
+    fn encode_args(&self) -> Vec<u8> {
+        match self {
+            Report::Result { hash, result } => (hash, result).encode(),
+            Report::Invalid { hash } => hash.encode(),
+        }
+    }
+}
+
+/// Chain specific values which are signed along with every extrinsic
+struct Chain {
+    genesis_hash: H256,
+    spec_version: u32,
+    transaction_version: u32,
+}
+
+/// Status of a watched extrinsic
+#[derive(Debug, PartialEq, Eq)]
+enum TransactionStatus {
+    Pending,
+    InBlock(String),
+    Failed(String),
+}
+
+#[derive(Default)]
+struct SubmitterState {
+    /// Next nonce of the executor account, `None` if it has to be fetched from the node
+    nonce: Option<u32>,
+    genesis_hash: Option<H256>,
+    metadata: MetadataCache,
+}
+
+/// Submits reports signed by the executor account
+pub struct Submitter {
+    client: Arc<WsClient>,
+    signer: Signer,
+    account: AccountId32,
+    state: Mutex<SubmitterState>,
+}
+
+impl Submitter {
+    pub fn new(client: Arc<WsClient>, signer: Signer) -> Submitter {
+        Submitter {
+            client,
+            account: signer.account(),
+            signer,
+            state: Mutex::new(SubmitterState::default()),
+        }
+    }
+
+    /// The executor account
+    pub fn account(&self) -> &AccountId32 {
+        &self.account
+    }
+
+    /// Signs and submits `report`, then waits until it is included in a block and returns the hash of that block.
+    /// Submissions are serialized, so that every extrinsic gets the next nonce.
+    pub async fn submit(&self, report: &Report) -> Result<String> {
+        let mut watch = {
+            let mut state = self.state.lock().await;
+            let nonce = match state.nonce {
+                Some(nonce) => nonce,
+                None => self.next_index().await?,
+            };
+            let extrinsic = self.build(&mut state, report, nonce).await?;
+
+            let watch = self
+                .client
+                .subscribe::<Value>(
+                    "author_submitAndWatchExtrinsic",
+                    rpc_params![format!("0x{}", hex::encode(&extrinsic))],
+                    "author_unwatchExtrinsic",
+                )
+                .await;
+            match watch {
+                Ok(watch) => {
+                    state.nonce = Some(nonce + 1);
+                    watch
+                }
+                Err(e) => {
+                    state.nonce = None;
+                    return Err(e).context("Extrinsic was rejected");
+                }
+            }
+        };
+
+        while let Some(status) = watch.next().await {
+            match transaction_status(&status?) {
+                TransactionStatus::Pending => {}
+                TransactionStatus::InBlock(block_hash) => return Ok(block_hash),
+                TransactionStatus::Failed(reason) => {
+                    self.state.lock().await.nonce = None;
+                    bail!("Extrinsic was not included: {}", reason);
+                }
+            }
+        }
+        bail!("Watching the extrinsic stopped before it was included")
+    }
+
+    /// Next nonce of the executor account, including extrinsics in the transaction pool
+    async fn next_index(&self) -> Result<u32> {
+        let index: u64 = self
+            .client
+            .request(
+                "system_accountNextIndex",
+                rpc_params![self.account.to_string()],
+            )
+            .await?;
+        u32::try_from(index).context("Nonce out of range")
+    }
+
+    async fn build(
+        &self,
+        state: &mut SubmitterState,
+        report: &Report,
+        nonce: u32,
+    ) -> Result<Vec<u8>> {
+        let block_hash: String = self
+            .client
+            .request("chain_getFinalizedHead", rpc_params![])
+            .await?;
+        let version: Value = self
+            .client
+            .request("state_getRuntimeVersion", rpc_params![&block_hash])
+            .await?;
+        let genesis_hash = match state.genesis_hash {
+            Some(genesis_hash) => genesis_hash,
+            None => {
+                let genesis_hash = parse_hash(&follow::block_hash(&self.client, 0).await?)?;
+                state.genesis_hash = Some(genesis_hash);
+                genesis_hash
+            }
+        };
+        let chain = Chain {
+            genesis_hash,
+            spec_version: version_number(&version, "specVersion")?,
+            transaction_version: version_number(&version, "transactionVersion")?,
+        };
+
+        let metadata = state
+            .metadata
+            .at(&self.client, &block_hash)
+            .await?
+            .ok_or_else(|| anyhow!("Runtime does not provide V14 metadata"))?;
+        let (pallet_index, call_index) = call_index(metadata, EXO_SYS_NAME, report.call_name())?;
+        let call = [vec![pallet_index, call_index], report.encode_args()].concat();
+
+        Ok(signed_extrinsic(&call, &self.signer, nonce, &chain))
+    }
+}
+
+/// Indices of the pallet and of the call, which prefix an encoded call
+fn call_index(metadata: &RuntimeMetadataV14, pallet: &str, call: &str) -> Result<(u8, u8)> {
+    let pallet = metadata
+        .pallets
+        .iter()
+        .find(|p| p.name == pallet)
+        .ok_or_else(|| anyhow!("Runtime has no pallet {}", pallet))?;
+    let calls = pallet
+        .calls
+        .as_ref()
+        .and_then(|calls| metadata.types.resolve(calls.ty.id()))
+        .ok_or_else(|| anyhow!("Pallet {} has no calls", pallet.name))?;
+
+    match calls.type_def() {
+        TypeDef::Variant(variants) => variants
+            .variants()
+            .iter()
+            .find(|variant| variant.name() == call)
+            .map(|variant| (pallet.index, variant.index()))
+            .ok_or_else(|| anyhow!("Pallet {} has no call {}", pallet.name, call)),
+        _ => bail!("Unexpected call type of pallet {}", pallet.name),
+    }
+}
+
+/// Encodes a signed extrinsic for the signed extensions `CheckNonZeroSender`, `CheckSpecVersion`, `CheckTxVersion`,
+/// `CheckGenesis`, `CheckEra`, `CheckNonce`, `CheckWeight` and `ChargeTransactionPayment` without tip
+fn signed_extrinsic(call: &[u8], signer: &Signer, nonce: u32, chain: &Chain) -> Vec<u8> {
+    let extra = (IMMORTAL_ERA, Compact(nonce), Compact(0u128)).encode();
+    // The era is immortal, so the era checkpoint is the genesis block
+    let additional = (
+        chain.spec_version,
+        chain.transaction_version,
+        chain.genesis_hash,
+        chain.genesis_hash,
+    )
+        .encode();
+
+    let payload = [call, extra.as_slice(), additional.as_slice()].concat();
+    let signature = if payload.len() > MAX_UNHASHED_PAYLOAD {
+        signer.sign(&blake2_256(&payload))
+    } else {
+        signer.sign(&payload)
+    };
+
+    let mut extrinsic = vec![SIGNED_FLAG | EXTRINSIC_VERSION, ADDRESS_ID];
+    extrinsic.extend(signer.public());
+    extrinsic.extend(signature);
+    extrinsic.extend(extra);
+    extrinsic.extend(call);
+
+    let mut encoded = Compact(extrinsic.len() as u32).encode();
+    encoded.extend(extrinsic);
+    encoded
+}
+
+/// Reads a status of `author_submitAndWatchExtrinsic`
+fn transaction_status(status: &Value) -> TransactionStatus {
+    match status {
+        Value::String(status) => match status.as_str() {
+            "future" | "ready" => TransactionStatus::Pending,
+            status => TransactionStatus::Failed(status.to_string()),
+        },
+        Value::Object(status) => {
+            if let Some(Value::String(block_hash)) =
+                status.get("inBlock").or_else(|| status.get("finalized"))
+            {
+                TransactionStatus::InBlock(block_hash.clone())
+            } else if status.contains_key("usurped") {
+                TransactionStatus::Failed(String::from("usurped"))
+            } else {
+                TransactionStatus::Pending
+            }
+        }
+        status => TransactionStatus::Failed(format!("unexpected status {}", status)),
+    }
+}
+
+fn parse_hash(hash: &str) -> Result<H256> {
+    let hash = unhex(hash, error::NotHex::Value).map_err(|e| anyhow!("Invalid hash: {:?}", e))?;
+    if hash.len() != H256::len_bytes() {
+        bail!("Invalid hash length {}", hash.len());
+    }
+    Ok(H256::from_slice(&hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_scale_codec::Decode;
+    use serde_json::json;
+    use sp_core::crypto::ByteArray;
+    use sp_core::{sr25519, Pair};
+
+    fn chain() -> Chain {
+        Chain {
+            genesis_hash: H256::repeat_byte(7),
+            spec_version: 100,
+            transaction_version: 1,
+        }
+    }
+
+    /// Splits a signed extrinsic into the signer, the signature and the signed payload
+    fn split(encoded: &[u8]) -> (sr25519::Public, sr25519::Signature, Vec<u8>) {
+        let input = &mut &encoded[..];
+        let length = <Compact<u32>>::decode(input).unwrap().0 as usize;
+        assert_eq!(input.len(), length);
+        assert_eq!(input[..2], [SIGNED_FLAG | EXTRINSIC_VERSION, ADDRESS_ID]);
+        // `MultiSignature::Sr25519`
+        assert_eq!(input[34], 1);
+
+        let signer = sr25519::Public::from_slice(&input[2..34]).unwrap();
+        let signature = sr25519::Signature::from_slice(&input[35..99]).unwrap();
+        let extra_and_call = &input[99..];
+        let additional = (100u32, 1u32, H256::repeat_byte(7), H256::repeat_byte(7)).encode();
+        // The extra is the immortal era, the compact nonce and the compact tip, the call follows
+        let extra_length = 1 + Compact(5u32).encode().len() + 1;
+        let payload = [
+            &extra_and_call[extra_length..],
+            &extra_and_call[..extra_length],
+            additional.as_slice(),
+        ]
+        .concat();
+        (signer, signature, payload)
+    }
+
+    #[test]
+    fn extrinsic_is_signed_by_executor() {
+        let signer = Signer::Sr25519(sr25519::Pair::from_string("//Alice", None).unwrap());
+        let report = Report::Result {
+            hash: H256::repeat_byte(1),
+            result: SUCCESS_RESULT.to_vec(),
+        };
+        let call = [vec![8, 2], report.encode_args()].concat();
+
+        let extrinsic = signed_extrinsic(&call, &signer, 5, &chain());
+
+        let (public, signature, payload) = split(&extrinsic);
+        assert_eq!(public.0, signer.public());
+        assert_eq!(payload[..call.len()], call[..]);
+        assert!(sr25519::Pair::verify(&signature, &payload, &public));
+    }
+
+    #[test]
+    fn long_payload_is_hashed() {
+        let signer = Signer::Sr25519(sr25519::Pair::from_string("//Alice", None).unwrap());
+        let report = Report::Result {
+            hash: H256::repeat_byte(1),
+            result: vec![1; 300],
+        };
+        let call = [vec![8, 2], report.encode_args()].concat();
+
+        let extrinsic = signed_extrinsic(&call, &signer, 5, &chain());
+
+        let (public, signature, payload) = split(&extrinsic);
+        assert!(!sr25519::Pair::verify(&signature, &payload, &public));
+        assert!(sr25519::Pair::verify(
+            &signature,
+            blake2_256(&payload),
+            &public
+        ));
+    }
+
+    #[test]
+    fn transaction_status_is_parsed() {
+        let block_hash = format!("{:?}", H256::repeat_byte(1));
+        assert_eq!(
+            transaction_status(&json!("ready")),
+            TransactionStatus::Pending
+        );
+        assert_eq!(
+            transaction_status(&json!({ "broadcast": ["peer"] })),
+            TransactionStatus::Pending
+        );
+        assert_eq!(
+            transaction_status(&json!({ "inBlock": block_hash })),
+            TransactionStatus::InBlock(block_hash.clone())
+        );
+        assert_eq!(
+            transaction_status(&json!({ "usurped": block_hash })),
+            TransactionStatus::Failed(String::from("usurped"))
+        );
+        assert_eq!(
+            transaction_status(&json!("invalid")),
+            TransactionStatus::Failed(String::from("invalid"))
+        );
+    }
+}
@@ -2,6 +2,17 @@
 
 # this program should do none of the thinking. it should only execute commands and save it.
 # things like hash checking and date getting should be done externaly
+#
+# The container runs without network, so cargo only uses what is in the image.
+# Exit codes, which tell exosysd how to report the run:
+#   0 - audited, no vulnerabilities found
+#   1 - audited, vulnerabilities found
+#   2 - the package can't be audited, e.g. it has no Cargo.toml
+#   3 - the tool failed
+EXIT_PASSED=0
+EXIT_FINDINGS=1
+EXIT_UNAUDITABLE=2
+EXIT_TOOL_ERROR=3
 
 POSITIONAL_ARGS=()
 
@@ -29,7 +40,7 @@ while [[ $# -gt 0 ]]; do
       ;;
     -*|--*)
       echo "Unknown option $1"
-      exit 1
+      exit $EXIT_TOOL_ERROR
       ;;
     *)
       POSITIONAL_ARGS+=("$1") # save positional arg
@@ -42,7 +53,7 @@ set -- "${POSITIONAL_ARGS[@]}" # restore positional parameters
 # We dont really need pos args but ill leave it in
 
 
-if [[ ! ($HASH && $DATE_READABLE && $DATE) ]]; then echo "Variables are not set"; exit 1; fi
+if [[ ! ($HASH && $DATE_READABLE && $DATE) ]]; then echo "Variables are not set"; exit $EXIT_TOOL_ERROR; fi
 if [[ $DEBUG == 1 ]]; then
   echo ""
   echo "----------------------"
@@ -75,7 +86,7 @@ function exec_audit {
     TOML_FILE="$(find "$EXTRACT_PATH" -name Cargo.toml)"
   else
     echo "Cannot Continue, neither Cargo.lock, or Cargo.toml was found"
-    exit 1
+    exit $EXIT_UNAUDITABLE
   fi
 
 
@@ -86,7 +97,11 @@ function exec_audit {
     #cd or remote shell into the correct dir
 
     #cargo generate-lockfile --manifest-path $(to_local_dir $TOML_FILE) -- 
-    ( cd $(dirname $TOML_FILE) && cargo generate-lockfile )
+    # Without network the dependencies can only be resolved from the crates in the image
+    if ! ( cd $(dirname $TOML_FILE) && cargo generate-lockfile --offline ); then
+      echo "Lockfile can't be generated offline, the package has to include its Cargo.lock"
+      exit $EXIT_UNAUDITABLE
+    fi
 
     LOCK_FILE="$(find "$EXTRACT_PATH" -name Cargo.lock)"
   fi
@@ -94,12 +109,22 @@ function exec_audit {
 
   touch "$REPORT_PATH"report.json
 
-  ( cd $(dirname $LOCK_FILE) && cargo audit --json > "$REPORT_PATH""report.json" )
+  # The advisory database is fetched when the image is built
+  ( cd $(dirname $LOCK_FILE) && cargo audit --no-fetch --json > "$REPORT_PATH""report.json" )
   # cargo audit --json > "$REPORT_PATH""report.json" # better save method. (?)
 
   # cp or symlink, whatever is better
   cp -r "$REPORT_PATH" "$TIMESTAMP_PATH"
 
+  # cargo audit exits with 1 for findings and for errors, only the report tells them apart
+  if grep -q '"vulnerabilities":{"found":true' "$REPORT_PATH""report.json"; then
+    exit $EXIT_FINDINGS
+  elif grep -q '"vulnerabilities":{"found":false' "$REPORT_PATH""report.json"; then
+    exit $EXIT_PASSED
+  fi
+  echo "cargo audit did not write a report"
+  exit $EXIT_TOOL_ERROR
+
 }
 
 
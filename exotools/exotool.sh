@@ -7,10 +7,14 @@
 # Author: QDAO Team
 # License: GNU AFFERO GENERAL PUBLIC LICENSE - Version 3, 19 November 2007
 
+# Exit codes are the ones of the audit script: 0 no findings, 1 findings, 2 the request can't be audited, 3 tool error
+EXIT_UNAUDITABLE=2
+EXIT_TOOL_ERROR=3
+
 # Dependency checks
-type curl >/dev/null || { echo >&2 "curl is missing. please install it." ; exit 1;}
-type docker >/dev/null || { echo >&2 "docker is missing. please install it." ; exit 1;}
-type keccak256 >/dev/null || { echo >&2 "keccak256 is missing. please install it." ; exit 1;}
+type curl >/dev/null || { echo >&2 "curl is missing. please install it." ; exit $EXIT_TOOL_ERROR;}
+type docker >/dev/null || { echo >&2 "docker is missing. please install it." ; exit $EXIT_TOOL_ERROR;}
+type keccak256 >/dev/null || { echo >&2 "keccak256 is missing. please install it." ; exit $EXIT_TOOL_ERROR;}
 
 # Process Args
 if (( $# != 2 )); then
@@ -22,7 +26,7 @@ regex='(https?)://[-[:alnum:]\+&@#/%?=~_|!:,.;]*[-[:alnum:]\+&@#/%=~_|]'
 if [[ ! $1 =~ $regex ]]
 then
   echo "[DEBUG] Invalid URL was passed to ExoTool."
-  exit 1
+  exit $EXIT_UNAUDITABLE
 fi
 
 # Step back because it is started from Daemon
@@ -64,7 +68,7 @@ SUPPLIED_HASH=$2
 # Prepare the security audit working folders
 function prep_folders {
 
-  if [[ ! $HASH ]]; then echo "[DEBUG] Hash passed to ExoTool was not set correctly."; exit 1; fi
+  if [[ ! $HASH ]]; then echo "[DEBUG] Hash passed to ExoTool was not set correctly."; exit $EXIT_TOOL_ERROR; fi
 
   MOUNTPOINT="$SCRIPT_PATH"/static/"$HASH"
   TIMESTAMP_PATH="$MOUNTPOINT"/reports/"$DATE_READABLE"/
@@ -84,18 +88,23 @@ function get_audit_files {
   TEMP_PATH="/tmp/qrucial"
   mkdir -p "$TEMP_PATH"/
   TEMP_PATH="$TEMP_PATH"/"$PROGRAM_NAME"   # This gets the final parts of url, should be the filename
-  curl -s "$URL" --output "$TEMP_PATH"
+  if ! curl -sf "$URL" --output "$TEMP_PATH"; then
+    echo "[DEBUG] $URL could not be downloaded"
+    exit $EXIT_TOOL_ERROR
+  fi
   echo "$TEMP_PATH"
 
   # Get hash of file
   HASH=$(cat "$TEMP_PATH" | keccak256)
+  # Files of an earlier run may have been replaced with links by the tool, so the job starts from scratch
+  rm -rf "$SCRIPT_PATH"/static/"$HASH"
   prep_folders
   mv "$TEMP_PATH" "$DOWNLOAD_PATH"
 
   # The file needs to be a tar file
   if ! { tar ztf "$DOWNLOAD_PATH"/"$PROGRAM_NAME" || tar tf "$DOWNLOAD_PATH"/"$PROGRAM_NAME"; } >/dev/null 2>&1; then
     echo "[DEBUG] $DOWNLOAD_PATH is not a tar file"
-    exit 1
+    exit $EXIT_UNAUDITABLE
   fi
 
   # Extract file. to program dir (to root directory of the docker/)
@@ -142,13 +151,19 @@ function to_absolute_dir () {
 # Run the proper commands to generate a report
 function exec_audit {
 ## Docker run $HASH parmiters x y z
-  docker run --name="$HASH" -v "$MOUNTPOINT":/exotools exotools \
+  # The tool processes untrusted packages, so it never runs as root
+  CONTAINER_USER="$(id -u):$(id -g)"
+  if [[ $(id -u) == 0 ]]; then
+    CONTAINER_USER="65534:65534"
+    chown -R -h "$CONTAINER_USER" "$MOUNTPOINT"
+  fi
+  docker run --name="$HASH" --network none --user "$CONTAINER_USER" -v "$MOUNTPOINT":/exotools exotools \
     /usr/exotools/audit_script.sh \
     -h $HASH \
     -d $DATE \
     -D $DATE_READABLE \
     --debug
-  # We should have more error handling here.
+  AUDIT_STATUS=$?
 }
 
 function call_logger {
@@ -165,7 +180,7 @@ function check_hash {
   echo ""
   if [[ ! $HASH == $SUPPLIED_HASH ]]; then
     echo "[DEBUG] Hashes don't match: $HASH != $SUPPLIED_HASH"
-    exit 1
+    exit $EXIT_UNAUDITABLE
   fi
   echo "[DEBUG] Hashes received do match."
   echo ""
@@ -183,11 +198,14 @@ prep_folders
 docker_prep 1
 ## Run Auditor script bundled in the docker image
 exec_audit
-## Notify the logger about execution
-call_logger
+## Notify the logger about execution, unless exosysd reports the result itself
+if [[ -z $EXOTOOL_NO_LOGGER ]]; then
+  call_logger
+fi
 
 # Ideas: Would it be smart to offload some of the logic here into the docker file?
 # We could Have a bash script that gets loaded into the docker
 
-# Exit
+# Exit with the result of the audit
 safe_exit 1
+exit $AUDIT_STATUS
@@ -45,7 +45,7 @@ sleep 5  # Wait for node start, TBA
 
 # Start ExoSys Daemon in background/tmux
 cd exosysd/target/debug/
-tmux new-session -d -s qdao-exosysd './qdao-exosysd'
+tmux new-session -d -s qdao-exosysd './qdao-exosysd --dev'
 
 # Start the QDAO API
 cd ~/QRUCIAL-DAO/
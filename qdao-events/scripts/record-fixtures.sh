@@ -0,0 +1,40 @@
+#!/bin/bash
+# Records the fixtures of the qdao-events tests from a running development node
+#
+# Usage: record-fixtures.sh <block hash> [node HTTP URL]
+#
+# Start `qdao-node --dev`, request a tool execution with `ExoSys.toolExecReq` (e.g. in polkadot.js apps) and pass the
+# hash of the block which includes it. The metadata and the `System::Events` storage of that block are written as hex
+# to tests/fixtures, the tests decode them and regenerate src/generated.rs from the metadata.
+
+type curl >/dev/null || { echo >&2 "curl is missing. Please install it." ; exit 1;}
+
+if (( $# < 1 )); then
+  echo >&2 "Usage: record-fixtures.sh <block hash> [node HTTP URL]"
+  exit 1
+fi
+
+BLOCK_HASH=$1
+NODE=${2:-http://127.0.0.1:9933}
+FIXTURES="$(dirname "$0")/../tests/fixtures"
+# twox_128("System") ++ twox_128("Events")
+EVENTS_KEY=0x26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7
+
+# Calls an RPC method and prints its hex result
+function rpc {
+  RESULT=$(curl -sf -H "Content-Type: application/json" \
+    -d "{\"id\":1,\"jsonrpc\":\"2.0\",\"method\":\"$1\",\"params\":$2}" "$NODE") || return 1
+  echo "$RESULT" | grep -o '"result":"0x[0-9a-f]*"' | cut -d '"' -f 4
+}
+
+mkdir -p "$FIXTURES"
+METADATA=$(rpc state_getMetadata "[\"$BLOCK_HASH\"]")
+EVENTS=$(rpc state_getStorage "[\"$EVENTS_KEY\", \"$BLOCK_HASH\"]")
+if [[ ! ($METADATA && $EVENTS) ]]; then
+  echo >&2 "Metadata or events of block $BLOCK_HASH could not be read from $NODE"
+  exit 1
+fi
+
+echo "$METADATA" > "$FIXTURES"/metadata.hex
+echo "$EVENTS" > "$FIXTURES"/events.hex
+echo "Recorded block $BLOCK_HASH to $FIXTURES"
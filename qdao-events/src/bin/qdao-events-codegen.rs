@@ -0,0 +1,42 @@
+//! Prints the event types of a runtime, generated from its metadata
+//!
+//! The metadata is read from a file containing either the SCALE encoded metadata or the hex string returned by
+//! `state_getMetadata`.
+
+use std::{env, fs, process};
+
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use parity_scale_codec::Decode;
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: qdao-events-codegen <metadata file>");
+            process::exit(1);
+        }
+    };
+
+    match generate(&path) {
+        Ok(code) => print!("{}", code),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn generate(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    let data = match std::str::from_utf8(&data) {
+        Ok(text) if text.trim().starts_with("0x") => {
+            hex::decode(text.trim().trim_start_matches("0x").trim_matches('"'))?
+        }
+        _ => data,
+    };
+
+    match RuntimeMetadataPrefixed::decode(&mut &data[..])?.1 {
+        RuntimeMetadata::V14(metadata) => Ok(qdao_events::codegen::generate(&metadata)?),
+        _ => Err(Box::from("Only V14 metadata is supported")),
+    }
+}
@@ -0,0 +1,315 @@
+//! Generation of Rust types for the events of a runtime from its metadata, in the style of subxt
+//!
+//! Every type reachable from `System::Events` is emitted once in a module tree mirroring its path below
+//! `runtime_types`. Generic types are emitted with the type arguments used by the runtime, bounded vectors and the
+//! standard wrappers are mapped to the types of `std` and `sp_core` which have the same encoding.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use frame_metadata::v14::RuntimeMetadataV14;
+use scale_info::{form::PortableForm, Field, PortableRegistry, Type, TypeDef, TypeDefPrimitive};
+
+use crate::{events_type, Error};
+
+const ROOT: &str = "runtime_types";
+
+const HEADER: &str = "//! Types of the runtime events, generated from the runtime metadata by `qdao-events-codegen`. Do not edit.
+//!
+//! Regenerate after runtime changes with
+//! `cargo run --bin qdao-events-codegen -- <metadata> | rustfmt --edition 2021 > src/generated.rs`
+#![allow(dead_code, unused_imports, non_camel_case_types, clippy::all)]
+
+";
+
+const DERIVES: &str = "#[derive(::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq)]";
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while", "async",
+    "await", "dyn",
+];
+
+/// Generated definitions, keyed by name to emit them in a stable order
+#[derive(Default)]
+struct Module {
+    modules: BTreeMap<String, Module>,
+    types: BTreeMap<String, String>,
+}
+
+impl Module {
+    fn write(&self, name: &str, code: &mut String) {
+        let _ = writeln!(code, "pub mod {} {{", name);
+        let _ = writeln!(code, "use super::{};", ROOT);
+        for (name, module) in &self.modules {
+            module.write(name, code);
+        }
+        for definition in self.types.values() {
+            code.push_str(definition);
+        }
+        code.push_str("}\n");
+    }
+}
+
+/// Generates the types of all events of the runtime, the result still has to be formatted with rustfmt
+pub fn generate(metadata: &RuntimeMetadataV14) -> Result<String, Error> {
+    generate_for_type(events_type(metadata)?.id(), &metadata.types)
+}
+
+/// Generates the types reachable from the event records of type `events`
+pub fn generate_for_type(events: u32, registry: &PortableRegistry) -> Result<String, Error> {
+    let generator = Generator { registry };
+    let mut root = Module::default();
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![events];
+
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        let ty = generator.resolve(id)?;
+        pending.extend(dependencies(ty));
+
+        let segments = ty.path().segments();
+        if let Some((name, path)) = segments.split_last() {
+            if is_mapped(name) {
+                continue;
+            }
+            let definition = generator.definition(name, ty)?;
+            let module = path.iter().fold(&mut root, |module, segment| {
+                module.modules.entry(segment.clone()).or_default()
+            });
+            if let Some(existing) = module.types.insert(name.clone(), definition.clone()) {
+                if existing != definition {
+                    return Err(Error::Codegen(format!(
+                        "{} is used with different type arguments",
+                        segments.join("::")
+                    )));
+                }
+            }
+        }
+    }
+
+    let mut code = String::from(HEADER);
+    let _ = writeln!(
+        code,
+        "pub type EventRecords = {};\n",
+        generator.type_name(events)?
+    );
+    root.write(ROOT, &mut code);
+    Ok(code)
+}
+
+/// Types which are mapped to existing types with the same encoding instead of being generated
+fn is_mapped(name: &str) -> bool {
+    matches!(
+        name,
+        "Option" | "Result" | "BoundedVec" | "WeakBoundedVec" | "AccountId32" | "H256"
+    )
+}
+
+/// Types referenced by `ty`
+fn dependencies(ty: &Type<PortableForm>) -> Vec<u32> {
+    match ty.type_def() {
+        TypeDef::Composite(composite) => composite
+            .fields()
+            .iter()
+            .map(|field| field.ty().id())
+            .collect(),
+        TypeDef::Variant(variant) => variant
+            .variants()
+            .iter()
+            .flat_map(|variant| variant.fields().iter().map(|field| field.ty().id()))
+            .collect(),
+        TypeDef::Sequence(sequence) => vec![sequence.type_param().id()],
+        TypeDef::Array(array) => vec![array.type_param().id()],
+        TypeDef::Tuple(tuple) => tuple.fields().iter().map(|field| field.id()).collect(),
+        TypeDef::Compact(compact) => vec![compact.type_param().id()],
+        TypeDef::Primitive(_) | TypeDef::BitSequence(_) => Vec::new(),
+    }
+}
+
+struct Generator<'a> {
+    registry: &'a PortableRegistry,
+}
+
+impl<'a> Generator<'a> {
+    fn resolve(&self, id: u32) -> Result<&'a Type<PortableForm>, Error> {
+        self.registry
+            .resolve(id)
+            .ok_or_else(|| Error::Codegen(format!("Type {} is not in the registry", id)))
+    }
+
+    /// Rust type of the type `id`, relative to a module of the generated tree
+    fn type_name(&self, id: u32) -> Result<String, Error> {
+        let ty = self.resolve(id)?;
+        let param = |index: usize| match ty.type_params().get(index).and_then(|param| param.ty()) {
+            Some(param) => self.type_name(param.id()),
+            None => Err(Error::Codegen(format!(
+                "Type {} misses a type parameter",
+                id
+            ))),
+        };
+
+        let segments = ty.path().segments();
+        match segments.last().map(String::as_str) {
+            Some("Option") => return Ok(format!("::core::option::Option<{}>", param(0)?)),
+            Some("Result") => {
+                return Ok(format!(
+                    "::core::result::Result<{}, {}>",
+                    param(0)?,
+                    param(1)?
+                ))
+            }
+            Some("BoundedVec") | Some("WeakBoundedVec") => {
+                return Ok(format!("::std::vec::Vec<{}>", param(0)?))
+            }
+            Some("AccountId32") => return Ok(String::from("::sp_core::crypto::AccountId32")),
+            Some("H256") => return Ok(String::from("::sp_core::H256")),
+            Some(_) => return Ok(format!("{}::{}", ROOT, segments.join("::"))),
+            None => {}
+        }
+
+        Ok(match ty.type_def() {
+            TypeDef::Sequence(sequence) => format!(
+                "::std::vec::Vec<{}>",
+                self.type_name(sequence.type_param().id())?
+            ),
+            TypeDef::Array(array) => format!(
+                "[{}; {}]",
+                self.type_name(array.type_param().id())?,
+                array.len()
+            ),
+            TypeDef::Tuple(tuple) => {
+                let fields = tuple
+                    .fields()
+                    .iter()
+                    .map(|field| self.type_name(field.id()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match fields.len() {
+                    1 => format!("({},)", fields[0]),
+                    _ => format!("({})", fields.join(", ")),
+                }
+            }
+            TypeDef::Compact(compact) => {
+                format!(
+                    "::parity_scale_codec::Compact<{}>",
+                    self.type_name(compact.type_param().id())?
+                )
+            }
+            TypeDef::Primitive(primitive) => String::from(match primitive {
+                TypeDefPrimitive::Bool => "bool",
+                TypeDefPrimitive::Char => "char",
+                TypeDefPrimitive::Str => "::std::string::String",
+                TypeDefPrimitive::U8 => "u8",
+                TypeDefPrimitive::U16 => "u16",
+                TypeDefPrimitive::U32 => "u32",
+                TypeDefPrimitive::U64 => "u64",
+                TypeDefPrimitive::U128 => "u128",
+                TypeDefPrimitive::I8 => "i8",
+                TypeDefPrimitive::I16 => "i16",
+                TypeDefPrimitive::I32 => "i32",
+                TypeDefPrimitive::I64 => "i64",
+                TypeDefPrimitive::I128 => "i128",
+                TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+                    return Err(Error::Codegen(String::from(
+                        "256 bit integers are not supported",
+                    )))
+                }
+            }),
+            TypeDef::Composite(_) | TypeDef::Variant(_) => {
+                return Err(Error::Codegen(format!("Type {} has no path", id)))
+            }
+            TypeDef::BitSequence(_) => {
+                return Err(Error::Codegen(String::from(
+                    "Bit sequences are not supported",
+                )))
+            }
+        })
+    }
+
+    /// Definition of the struct or enum `name`
+    fn definition(&self, name: &str, ty: &Type<PortableForm>) -> Result<String, Error> {
+        let mut code = format!("{}\n", DERIVES);
+        match ty.type_def() {
+            TypeDef::Composite(composite) => {
+                let fields = self.fields(composite.fields(), "pub ")?;
+                let terminator = if is_named(composite.fields()) {
+                    ""
+                } else {
+                    ";"
+                };
+                let _ = writeln!(code, "pub struct {}{}{}", name, fields, terminator);
+            }
+            TypeDef::Variant(variant) => {
+                let _ = writeln!(code, "pub enum {} {{", name);
+                for variant in variant.variants() {
+                    let _ = writeln!(code, "#[codec(index = {})]", variant.index());
+                    let _ = writeln!(
+                        code,
+                        "{}{},",
+                        variant.name(),
+                        self.fields(variant.fields(), "")?
+                    );
+                }
+                code.push_str("}\n");
+            }
+            _ => {
+                return Err(Error::Codegen(format!(
+                    "{} is neither a struct nor an enum",
+                    name
+                )))
+            }
+        }
+        Ok(code)
+    }
+
+    /// Named fields in braces, unnamed fields in parentheses
+    fn fields(&self, fields: &[Field<PortableForm>], visibility: &str) -> Result<String, Error> {
+        if fields.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut code = String::new();
+        if is_named(fields) {
+            code.push_str(" {\n");
+            for field in fields {
+                let name = field.name().map(String::as_str).unwrap_or_default();
+                let _ = writeln!(
+                    code,
+                    "{}{}: {},",
+                    visibility,
+                    field_name(name),
+                    self.type_name(field.ty().id())?
+                );
+            }
+            code.push('}');
+        } else {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    Ok(format!(
+                        "{}{}",
+                        visibility,
+                        self.type_name(field.ty().id())?
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let _ = write!(code, "({})", fields.join(", "));
+        }
+        Ok(code)
+    }
+}
+
+fn is_named(fields: &[Field<PortableForm>]) -> bool {
+    fields.first().is_some_and(|field| field.name().is_some())
+}
+
+fn field_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
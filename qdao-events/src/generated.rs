@@ -0,0 +1,745 @@
+//! Types of the runtime events, generated from the runtime metadata by `qdao-events-codegen`. Do not edit.
+//!
+//! Regenerate after runtime changes with
+//! `cargo run --bin qdao-events-codegen -- <metadata> | rustfmt --edition 2021 > src/generated.rs`
+#![allow(dead_code, unused_imports, non_camel_case_types, clippy::all)]
+
+pub type EventRecords = ::std::vec::Vec<runtime_types::frame_system::EventRecord>;
+
+pub mod runtime_types {
+    use super::runtime_types;
+    pub mod frame_support {
+        use super::runtime_types;
+        pub mod dispatch {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum DispatchClass {
+                #[codec(index = 0)]
+                Normal,
+                #[codec(index = 1)]
+                Operational,
+                #[codec(index = 2)]
+                Mandatory,
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub struct DispatchInfo {
+                pub weight: runtime_types::sp_weights::weight_v2::Weight,
+                pub class: runtime_types::frame_support::dispatch::DispatchClass,
+                pub pays_fee: runtime_types::frame_support::dispatch::Pays,
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Pays {
+                #[codec(index = 0)]
+                Yes,
+                #[codec(index = 1)]
+                No,
+            }
+        }
+        pub mod traits {
+            use super::runtime_types;
+            pub mod tokens {
+                use super::runtime_types;
+                pub mod misc {
+                    use super::runtime_types;
+                    #[derive(
+                        ::parity_scale_codec::Decode,
+                        ::parity_scale_codec::Encode,
+                        Debug,
+                        Clone,
+                        PartialEq,
+                        Eq,
+                    )]
+                    pub enum BalanceStatus {
+                        #[codec(index = 0)]
+                        Free,
+                        #[codec(index = 1)]
+                        Reserved,
+                    }
+                }
+            }
+        }
+    }
+    pub mod frame_system {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                ExtrinsicSuccess {
+                    dispatch_info: runtime_types::frame_support::dispatch::DispatchInfo,
+                },
+                #[codec(index = 1)]
+                ExtrinsicFailed {
+                    dispatch_error: runtime_types::sp_runtime::DispatchError,
+                    dispatch_info: runtime_types::frame_support::dispatch::DispatchInfo,
+                },
+                #[codec(index = 2)]
+                CodeUpdated,
+                #[codec(index = 3)]
+                NewAccount {
+                    account: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 4)]
+                KilledAccount {
+                    account: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 5)]
+                Remarked {
+                    sender: ::sp_core::crypto::AccountId32,
+                    hash: ::sp_core::H256,
+                },
+            }
+        }
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub struct EventRecord {
+            pub phase: runtime_types::frame_system::Phase,
+            pub event: runtime_types::qdao_runtime::RuntimeEvent,
+            pub topics: ::std::vec::Vec<::sp_core::H256>,
+        }
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum Phase {
+            #[codec(index = 0)]
+            ApplyExtrinsic(u32),
+            #[codec(index = 1)]
+            Finalization,
+            #[codec(index = 2)]
+            Initialization,
+        }
+    }
+    pub mod pallet_balances {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                Endowed {
+                    account: ::sp_core::crypto::AccountId32,
+                    free_balance: u128,
+                },
+                #[codec(index = 1)]
+                DustLost {
+                    account: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 2)]
+                Transfer {
+                    from: ::sp_core::crypto::AccountId32,
+                    to: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 3)]
+                BalanceSet {
+                    who: ::sp_core::crypto::AccountId32,
+                    free: u128,
+                    reserved: u128,
+                },
+                #[codec(index = 4)]
+                Reserved {
+                    who: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 5)]
+                Unreserved {
+                    who: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 6)]
+                ReserveRepatriated {
+                    from: ::sp_core::crypto::AccountId32,
+                    to: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                    destination_status:
+                        runtime_types::frame_support::traits::tokens::misc::BalanceStatus,
+                },
+                #[codec(index = 7)]
+                Deposit {
+                    who: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 8)]
+                Withdraw {
+                    who: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 9)]
+                Slashed {
+                    who: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+            }
+        }
+    }
+    pub mod pallet_grandpa {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                NewAuthorities {
+                    authority_set:
+                        ::std::vec::Vec<(runtime_types::sp_finality_grandpa::app::Public, u64)>,
+                },
+                #[codec(index = 1)]
+                Paused,
+                #[codec(index = 2)]
+                Resumed,
+            }
+        }
+    }
+    pub mod pallet_identity {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                IdentitySet { who: ::sp_core::crypto::AccountId32 },
+                #[codec(index = 1)]
+                IdentityCleared {
+                    who: ::sp_core::crypto::AccountId32,
+                    deposit: u128,
+                },
+                #[codec(index = 2)]
+                IdentityKilled {
+                    who: ::sp_core::crypto::AccountId32,
+                    deposit: u128,
+                },
+                #[codec(index = 3)]
+                JudgementRequested {
+                    who: ::sp_core::crypto::AccountId32,
+                    registrar_index: u32,
+                },
+                #[codec(index = 4)]
+                JudgementUnrequested {
+                    who: ::sp_core::crypto::AccountId32,
+                    registrar_index: u32,
+                },
+                #[codec(index = 5)]
+                JudgementGiven {
+                    target: ::sp_core::crypto::AccountId32,
+                    registrar_index: u32,
+                },
+                #[codec(index = 6)]
+                RegistrarAdded { registrar_index: u32 },
+                #[codec(index = 7)]
+                SubIdentityAdded {
+                    sub: ::sp_core::crypto::AccountId32,
+                    main: ::sp_core::crypto::AccountId32,
+                    deposit: u128,
+                },
+                #[codec(index = 8)]
+                SubIdentityRemoved {
+                    sub: ::sp_core::crypto::AccountId32,
+                    main: ::sp_core::crypto::AccountId32,
+                    deposit: u128,
+                },
+                #[codec(index = 9)]
+                SubIdentityRevoked {
+                    sub: ::sp_core::crypto::AccountId32,
+                    main: ::sp_core::crypto::AccountId32,
+                    deposit: u128,
+                },
+            }
+        }
+    }
+    pub mod pallet_sudo {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                Sudid {
+                    sudo_result:
+                        ::core::result::Result<(), runtime_types::sp_runtime::DispatchError>,
+                },
+                #[codec(index = 1)]
+                KeyChanged {
+                    old_sudoer: ::core::option::Option<::sp_core::crypto::AccountId32>,
+                },
+                #[codec(index = 2)]
+                SudoAsDone {
+                    sudo_result:
+                        ::core::result::Result<(), runtime_types::sp_runtime::DispatchError>,
+                },
+            }
+        }
+    }
+    pub mod pallet_transaction_payment {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                TransactionFeePaid {
+                    who: ::sp_core::crypto::AccountId32,
+                    actual_fee: u128,
+                    tip: u128,
+                },
+            }
+        }
+    }
+    pub mod qdao_audit_pallet {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Domain {
+                #[codec(index = 0)]
+                Substrate,
+                #[codec(index = 1)]
+                Ink,
+                #[codec(index = 2)]
+                Solidity,
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                SignedUp { who: ::sp_core::crypto::AccountId32 },
+                #[codec(index = 1)]
+                GameResult {
+                    player0: ::sp_core::crypto::AccountId32,
+                    player1: ::sp_core::crypto::AccountId32,
+                    winner: runtime_types::qdao_audit_pallet::pallet::Winner,
+                    domain:
+                        ::core::option::Option<runtime_types::qdao_audit_pallet::pallet::Domain>,
+                    review_hash: ::core::option::Option<::sp_core::H256>,
+                    severity: runtime_types::qdao_audit_pallet::pallet::Severity,
+                },
+                #[codec(index = 2)]
+                AccountCancelled {
+                    who: ::sp_core::crypto::AccountId32,
+                    unlock_at: u32,
+                },
+                #[codec(index = 3)]
+                Withdrawn { who: ::sp_core::crypto::AccountId32 },
+                #[codec(index = 4)]
+                ApprovalRevoked {
+                    approver: ::sp_core::crypto::AccountId32,
+                    approvee: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 5)]
+                ApplicationExpired { who: ::sp_core::crypto::AccountId32 },
+                #[codec(index = 6)]
+                ChallengeOpened {
+                    id: u32,
+                    challenger: ::sp_core::crypto::AccountId32,
+                    defendant: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 7)]
+                ChallengeAccepted { id: u32 },
+                #[codec(index = 8)]
+                JuryVoted {
+                    id: u32,
+                    juror: ::sp_core::crypto::AccountId32,
+                    winner: runtime_types::qdao_audit_pallet::pallet::Winner,
+                },
+                #[codec(index = 9)]
+                ChallengeResolved {
+                    id: u32,
+                    winner: runtime_types::qdao_audit_pallet::pallet::Winner,
+                    rated: bool,
+                },
+                #[codec(index = 10)]
+                StakeChanged {
+                    who: ::sp_core::crypto::AccountId32,
+                    stake: u128,
+                },
+                #[codec(index = 11)]
+                Endorsed {
+                    endorser: ::sp_core::crypto::AccountId32,
+                    auditor: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 12)]
+                EndorsementWithdrawn {
+                    endorser: ::sp_core::crypto::AccountId32,
+                    auditor: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 13)]
+                EndorsementsSlashed {
+                    auditor: ::sp_core::crypto::AccountId32,
+                    winner: ::sp_core::crypto::AccountId32,
+                    amount: u128,
+                },
+                #[codec(index = 14)]
+                FeeDistributed {
+                    auditor: ::sp_core::crypto::AccountId32,
+                    fee: u128,
+                    endorser_share: u128,
+                },
+                #[codec(index = 15)]
+                TeamCreated {
+                    team: u32,
+                    admin: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 16)]
+                TeamInviteSent {
+                    team: u32,
+                    who: ::sp_core::crypto::AccountId32,
+                    shares: u32,
+                },
+                #[codec(index = 17)]
+                TeamJoined {
+                    team: u32,
+                    who: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 18)]
+                TeamLeft {
+                    team: u32,
+                    who: ::sp_core::crypto::AccountId32,
+                },
+                #[codec(index = 19)]
+                TeamDissolved { team: u32 },
+                #[codec(index = 20)]
+                TeamSharesSet {
+                    team: u32,
+                    who: ::sp_core::crypto::AccountId32,
+                    shares: u32,
+                },
+                #[codec(index = 21)]
+                TeamFeeDistributed { team: u32, fee: u128 },
+                #[codec(index = 22)]
+                PrivilegeThresholdSet {
+                    privilege: runtime_types::qdao_audit_pallet::pallet::Privilege,
+                    threshold: ::core::option::Option<u32>,
+                },
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Privilege {
+                #[codec(index = 0)]
+                ClaimReview,
+                #[codec(index = 1)]
+                JuryDuty,
+                #[codec(index = 2)]
+                ApproveAuditors,
+                #[codec(index = 3)]
+                ProposeToolChange,
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Severity {
+                #[codec(index = 0)]
+                Low,
+                #[codec(index = 1)]
+                Medium,
+                #[codec(index = 2)]
+                High,
+                #[codec(index = 3)]
+                Critical,
+            }
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Winner {
+                #[codec(index = 0)]
+                Player0,
+                #[codec(index = 1)]
+                Player1,
+                #[codec(index = 2)]
+                Draw,
+            }
+        }
+    }
+    pub mod qdao_exo_pallet {
+        use super::runtime_types;
+        pub mod pallet {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub enum Event {
+                #[codec(index = 0)]
+                ExecutionRequest {
+                    who: ::sp_core::crypto::AccountId32,
+                    url: ::std::vec::Vec<u8>,
+                    hash: ::sp_core::H256,
+                },
+                #[codec(index = 1)]
+                ExecutionFinish {
+                    ret_hash: ::sp_core::H256,
+                    ret_result: ::std::vec::Vec<u8>,
+                },
+                #[codec(index = 2)]
+                ReviewClaimed {
+                    who: ::sp_core::crypto::AccountId32,
+                    hash: ::sp_core::H256,
+                },
+                #[codec(index = 3)]
+                ReviewClaimedByTeam {
+                    who: ::sp_core::crypto::AccountId32,
+                    team: u32,
+                    hash: ::sp_core::H256,
+                },
+                #[codec(index = 4)]
+                ReviewUnclaimed {
+                    who: ::sp_core::crypto::AccountId32,
+                    hash: ::sp_core::H256,
+                },
+                #[codec(index = 5)]
+                ReviewCompleted {
+                    who: ::sp_core::crypto::AccountId32,
+                    hash: ::sp_core::H256,
+                    result: u32,
+                },
+            }
+        }
+    }
+    pub mod qdao_runtime {
+        use super::runtime_types;
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum RuntimeEvent {
+            #[codec(index = 0)]
+            System(runtime_types::frame_system::pallet::Event),
+            #[codec(index = 4)]
+            Grandpa(runtime_types::pallet_grandpa::pallet::Event),
+            #[codec(index = 5)]
+            Balances(runtime_types::pallet_balances::pallet::Event),
+            #[codec(index = 6)]
+            TransactionPayment(runtime_types::pallet_transaction_payment::pallet::Event),
+            #[codec(index = 7)]
+            Sudo(runtime_types::pallet_sudo::pallet::Event),
+            #[codec(index = 8)]
+            ExoSys(runtime_types::qdao_exo_pallet::pallet::Event),
+            #[codec(index = 9)]
+            AuditModule(runtime_types::qdao_audit_pallet::pallet::Event),
+            #[codec(index = 10)]
+            Identity(runtime_types::pallet_identity::pallet::Event),
+        }
+    }
+    pub mod sp_arithmetic {
+        use super::runtime_types;
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum ArithmeticError {
+            #[codec(index = 0)]
+            Underflow,
+            #[codec(index = 1)]
+            Overflow,
+            #[codec(index = 2)]
+            DivisionByZero,
+        }
+    }
+    pub mod sp_core {
+        use super::runtime_types;
+        pub mod ed25519 {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub struct Public(pub [u8; 32]);
+        }
+    }
+    pub mod sp_finality_grandpa {
+        use super::runtime_types;
+        pub mod app {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub struct Public(pub runtime_types::sp_core::ed25519::Public);
+        }
+    }
+    pub mod sp_runtime {
+        use super::runtime_types;
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum DispatchError {
+            #[codec(index = 0)]
+            Other,
+            #[codec(index = 1)]
+            CannotLookup,
+            #[codec(index = 2)]
+            BadOrigin,
+            #[codec(index = 3)]
+            Module(runtime_types::sp_runtime::ModuleError),
+            #[codec(index = 4)]
+            ConsumerRemaining,
+            #[codec(index = 5)]
+            NoProviders,
+            #[codec(index = 6)]
+            TooManyConsumers,
+            #[codec(index = 7)]
+            Token(runtime_types::sp_runtime::TokenError),
+            #[codec(index = 8)]
+            Arithmetic(runtime_types::sp_arithmetic::ArithmeticError),
+            #[codec(index = 9)]
+            Transactional(runtime_types::sp_runtime::TransactionalError),
+        }
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub struct ModuleError {
+            pub index: u8,
+            pub error: [u8; 4],
+        }
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum TokenError {
+            #[codec(index = 0)]
+            NoFunds,
+            #[codec(index = 1)]
+            WouldDie,
+            #[codec(index = 2)]
+            BelowMinimum,
+            #[codec(index = 3)]
+            CannotCreate,
+            #[codec(index = 4)]
+            UnknownAsset,
+            #[codec(index = 5)]
+            Frozen,
+            #[codec(index = 6)]
+            Unsupported,
+        }
+        #[derive(
+            ::parity_scale_codec::Decode, ::parity_scale_codec::Encode, Debug, Clone, PartialEq, Eq,
+        )]
+        pub enum TransactionalError {
+            #[codec(index = 0)]
+            LimitReached,
+            #[codec(index = 1)]
+            NoLayer,
+        }
+    }
+    pub mod sp_weights {
+        use super::runtime_types;
+        pub mod weight_v2 {
+            use super::runtime_types;
+            #[derive(
+                ::parity_scale_codec::Decode,
+                ::parity_scale_codec::Encode,
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+            )]
+            pub struct Weight {
+                pub ref_time: u64,
+            }
+        }
+    }
+}
@@ -0,0 +1,689 @@
+//! Typed decoding of the events emitted by the QDAO pallets `ExoSys` and `AuditModule`
+//!
+//! The events are either decoded dynamically with the type information of the runtime metadata, or statically with
+//! the types generated from the metadata by [`codegen`], which turns changes of the events into compile errors. The
+//! crate does not depend on the runtime in both cases. Events of other pallets are skipped.
+
+use std::any::TypeId;
+use std::fmt;
+
+use frame_metadata::v14::{RuntimeMetadataV14, StorageEntryType};
+use scale_info::{interner::UntrackedSymbol, PortableRegistry};
+use sp_core::{crypto::AccountId32, H256};
+use substrate_parser::{
+    cards::{FieldData, ParsedData, Sequence, VariantData},
+    decode_all_as_type,
+};
+
+pub mod codegen;
+pub mod generated;
+mod typed;
+
+#[cfg(test)]
+mod tests;
+
+pub use typed::decode_events_static;
+
+const SYSTEM: &str = "System";
+const EVENTS: &str = "Events";
+const EVENT: &str = "event";
+const EXO_SYS: &str = "ExoSys";
+const AUDIT_MODULE: &str = "AuditModule";
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+pub type BlockNumber = u32;
+pub type TeamId = u32;
+
+/// Errors which occur while decoding events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The metadata has no `System::Events` storage entry
+    NoEventsEntry,
+    /// The event records could not be parsed with the type information of the metadata
+    Parser(String),
+    /// The parsed event records do not have the expected structure
+    UnexpectedFormat(&'static str),
+    /// The event is not known to this crate, the runtime is probably newer
+    UnknownEvent { pallet: &'static str, event: String },
+    /// A field of an event is missing or has an unexpected type
+    InvalidField {
+        event: &'static str,
+        field: &'static str,
+    },
+    /// The event records could not be decoded with the generated types
+    Codec(String),
+    /// The metadata contains types which can't be generated
+    Codegen(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoEventsEntry => write!(f, "Metadata has no System::Events storage entry"),
+            Error::Parser(e) => write!(f, "Events could not be parsed: {}", e),
+            Error::UnexpectedFormat(e) => write!(f, "Unexpected event format: {}", e),
+            Error::UnknownEvent { pallet, event } => {
+                write!(f, "Unknown event {}::{}", pallet, event)
+            }
+            Error::InvalidField { event, field } => {
+                write!(
+                    f,
+                    "Field {} of event {} is missing or invalid",
+                    field, event
+                )
+            }
+            Error::Codec(e) => write!(f, "Events could not be decoded: {}", e),
+            Error::Codegen(e) => write!(f, "Types could not be generated: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Enums of the pallets which are encoded as plain variants
+trait FromVariantName: Sized {
+    fn from_variant_name(name: &str) -> Option<Self>;
+}
+
+macro_rules! plain_enum {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl FromVariantName for $name {
+            fn from_variant_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($variant) => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+plain_enum!(
+    /// Result of a game between two auditors
+    Winner { Player0, Player1, Draw }
+);
+plain_enum!(
+    /// Domain an auditor can be specialized in
+    Domain { Substrate, Ink, Solidity }
+);
+plain_enum!(
+    /// Severity of a missed finding
+    Severity { Low, Medium, High, Critical }
+);
+plain_enum!(
+    /// Score-gated privilege of auditors
+    Privilege { ClaimReview, JuryDuty, ApproveAuditors, ProposeToolChange }
+);
+
+/// Events of the `ExoSys` pallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExoSysEvent {
+    ExecutionRequest {
+        who: AccountId,
+        url: Vec<u8>,
+        hash: H256,
+    },
+    ExecutionFinish {
+        ret_hash: H256,
+        ret_result: Vec<u8>,
+    },
+    ReviewClaimed {
+        who: AccountId,
+        hash: H256,
+    },
+    ReviewClaimedByTeam {
+        who: AccountId,
+        team: TeamId,
+        hash: H256,
+    },
+    ReviewUnclaimed {
+        who: AccountId,
+        hash: H256,
+    },
+    ReviewCompleted {
+        who: AccountId,
+        hash: H256,
+        result: u32,
+    },
+}
+
+/// Events of the `AuditModule` pallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    SignedUp {
+        who: AccountId,
+    },
+    GameResult {
+        player0: AccountId,
+        player1: AccountId,
+        winner: Winner,
+        domain: Option<Domain>,
+        review_hash: Option<H256>,
+        severity: Severity,
+    },
+    AccountCancelled {
+        who: AccountId,
+        unlock_at: BlockNumber,
+    },
+    Withdrawn {
+        who: AccountId,
+    },
+    ApprovalRevoked {
+        approver: AccountId,
+        approvee: AccountId,
+    },
+    ApplicationExpired {
+        who: AccountId,
+    },
+    ChallengeOpened {
+        id: u32,
+        challenger: AccountId,
+        defendant: AccountId,
+    },
+    ChallengeAccepted {
+        id: u32,
+    },
+    JuryVoted {
+        id: u32,
+        juror: AccountId,
+        winner: Winner,
+    },
+    ChallengeResolved {
+        id: u32,
+        winner: Winner,
+        rated: bool,
+    },
+    StakeChanged {
+        who: AccountId,
+        stake: Balance,
+    },
+    Endorsed {
+        endorser: AccountId,
+        auditor: AccountId,
+        amount: Balance,
+    },
+    EndorsementWithdrawn {
+        endorser: AccountId,
+        auditor: AccountId,
+        amount: Balance,
+    },
+    EndorsementsSlashed {
+        auditor: AccountId,
+        winner: AccountId,
+        amount: Balance,
+    },
+    FeeDistributed {
+        auditor: AccountId,
+        fee: Balance,
+        endorser_share: Balance,
+    },
+    TeamCreated {
+        team: TeamId,
+        admin: AccountId,
+    },
+    TeamInviteSent {
+        team: TeamId,
+        who: AccountId,
+        shares: u32,
+    },
+    TeamJoined {
+        team: TeamId,
+        who: AccountId,
+    },
+    TeamLeft {
+        team: TeamId,
+        who: AccountId,
+    },
+    TeamDissolved {
+        team: TeamId,
+    },
+    TeamSharesSet {
+        team: TeamId,
+        who: AccountId,
+        shares: u32,
+    },
+    TeamFeeDistributed {
+        team: TeamId,
+        fee: Balance,
+    },
+    PrivilegeThresholdSet {
+        privilege: Privilege,
+        threshold: Option<u32>,
+    },
+}
+
+/// An event of one of the QDAO pallets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QdaoEvent {
+    ExoSys(ExoSysEvent),
+    AuditModule(AuditEvent),
+}
+
+/// Decodes the QDAO events of a block from the raw value of the `System::Events` storage entry
+pub fn decode_events(metadata: &RuntimeMetadataV14, data: &[u8]) -> Result<Vec<QdaoEvent>, Error> {
+    decode_events_as_type(&events_type(metadata)?, data, &metadata.types)
+}
+
+/// Type of the event records in the `System::Events` storage entry
+fn events_type(metadata: &RuntimeMetadataV14) -> Result<UntrackedSymbol<TypeId>, Error> {
+    metadata
+        .pallets
+        .iter()
+        .filter_map(|pallet| pallet.storage.as_ref())
+        .find(|storage| storage.prefix == SYSTEM)
+        .and_then(|storage| storage.entries.iter().find(|entry| entry.name == EVENTS))
+        .map(|entry| match &entry.ty {
+            StorageEntryType::Plain(ty) => *ty,
+            StorageEntryType::Map { value, .. } => *value,
+        })
+        .ok_or(Error::NoEventsEntry)
+}
+
+/// Decodes the QDAO events from event records of type `ty`
+pub fn decode_events_as_type(
+    ty: &UntrackedSymbol<TypeId>,
+    data: &[u8],
+    registry: &PortableRegistry,
+) -> Result<Vec<QdaoEvent>, Error> {
+    let parsed =
+        decode_all_as_type(ty, data, registry).map_err(|e| Error::Parser(format!("{:?}", e)))?;
+    let records = match parsed.data {
+        ParsedData::SequenceRaw(records) => records.data,
+        _ => return Err(Error::UnexpectedFormat("event records are not a sequence")),
+    };
+
+    let mut events = Vec::new();
+    for record in records {
+        let fields = match record {
+            ParsedData::Composite(fields) => fields,
+            _ => return Err(Error::UnexpectedFormat("event record is not a struct")),
+        };
+        for field in fields {
+            if field.field_name.as_deref() != Some(EVENT) {
+                continue;
+            }
+            match field.data.data {
+                ParsedData::Variant(pallet) => events.extend(decode_pallet_event(pallet)?),
+                _ => return Err(Error::UnexpectedFormat("runtime event is not an enum")),
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Decodes the event of a QDAO pallet, returns `None` for events of other pallets
+fn decode_pallet_event(pallet: VariantData) -> Result<Option<QdaoEvent>, Error> {
+    let pallet_name = match pallet.variant_name.as_str() {
+        EXO_SYS => EXO_SYS,
+        AUDIT_MODULE => AUDIT_MODULE,
+        _ => return Ok(None),
+    };
+    let event = match pallet
+        .fields
+        .into_iter()
+        .next()
+        .map(|field| field.data.data)
+    {
+        Some(ParsedData::Variant(event)) => event,
+        _ => return Err(Error::UnexpectedFormat("pallet event is not an enum")),
+    };
+
+    let event = if pallet_name == EXO_SYS {
+        QdaoEvent::ExoSys(decode_exo_sys_event(event)?)
+    } else {
+        QdaoEvent::AuditModule(decode_audit_event(event)?)
+    };
+    Ok(Some(event))
+}
+
+fn decode_exo_sys_event(event: VariantData) -> Result<ExoSysEvent, Error> {
+    let name = event.variant_name.as_str();
+    Ok(match name {
+        "ExecutionRequest" => {
+            let f = Fields::new("ExecutionRequest", &event.fields);
+            ExoSysEvent::ExecutionRequest {
+                who: f.account("who")?,
+                url: f.bytes("url")?,
+                hash: f.hash("hash")?,
+            }
+        }
+        "ExecutionFinish" => {
+            let f = Fields::new("ExecutionFinish", &event.fields);
+            ExoSysEvent::ExecutionFinish {
+                ret_hash: f.hash("ret_hash")?,
+                ret_result: f.bytes("ret_result")?,
+            }
+        }
+        "ReviewClaimed" => {
+            let f = Fields::new("ReviewClaimed", &event.fields);
+            ExoSysEvent::ReviewClaimed {
+                who: f.account("who")?,
+                hash: f.hash("hash")?,
+            }
+        }
+        "ReviewClaimedByTeam" => {
+            let f = Fields::new("ReviewClaimedByTeam", &event.fields);
+            ExoSysEvent::ReviewClaimedByTeam {
+                who: f.account("who")?,
+                team: f.u32("team")?,
+                hash: f.hash("hash")?,
+            }
+        }
+        "ReviewUnclaimed" => {
+            let f = Fields::new("ReviewUnclaimed", &event.fields);
+            ExoSysEvent::ReviewUnclaimed {
+                who: f.account("who")?,
+                hash: f.hash("hash")?,
+            }
+        }
+        "ReviewCompleted" => {
+            let f = Fields::new("ReviewCompleted", &event.fields);
+            ExoSysEvent::ReviewCompleted {
+                who: f.account("who")?,
+                hash: f.hash("hash")?,
+                result: f.u32("result")?,
+            }
+        }
+        _ => {
+            return Err(Error::UnknownEvent {
+                pallet: EXO_SYS,
+                event: name.to_string(),
+            })
+        }
+    })
+}
+
+fn decode_audit_event(event: VariantData) -> Result<AuditEvent, Error> {
+    let name = event.variant_name.as_str();
+    Ok(match name {
+        "SignedUp" => {
+            let f = Fields::new("SignedUp", &event.fields);
+            AuditEvent::SignedUp {
+                who: f.account("who")?,
+            }
+        }
+        "GameResult" => {
+            let f = Fields::new("GameResult", &event.fields);
+            AuditEvent::GameResult {
+                player0: f.account("player0")?,
+                player1: f.account("player1")?,
+                winner: f.variant("winner")?,
+                domain: f.optional("domain", |f, data| f.variant_of("domain", data))?,
+                review_hash: f.optional("review_hash", |f, data| f.hash_of("review_hash", data))?,
+                severity: f.variant("severity")?,
+            }
+        }
+        "AccountCancelled" => {
+            let f = Fields::new("AccountCancelled", &event.fields);
+            AuditEvent::AccountCancelled {
+                who: f.account("who")?,
+                unlock_at: f.u32("unlock_at")?,
+            }
+        }
+        "Withdrawn" => {
+            let f = Fields::new("Withdrawn", &event.fields);
+            AuditEvent::Withdrawn {
+                who: f.account("who")?,
+            }
+        }
+        "ApprovalRevoked" => {
+            let f = Fields::new("ApprovalRevoked", &event.fields);
+            AuditEvent::ApprovalRevoked {
+                approver: f.account("approver")?,
+                approvee: f.account("approvee")?,
+            }
+        }
+        "ApplicationExpired" => {
+            let f = Fields::new("ApplicationExpired", &event.fields);
+            AuditEvent::ApplicationExpired {
+                who: f.account("who")?,
+            }
+        }
+        "ChallengeOpened" => {
+            let f = Fields::new("ChallengeOpened", &event.fields);
+            AuditEvent::ChallengeOpened {
+                id: f.u32("id")?,
+                challenger: f.account("challenger")?,
+                defendant: f.account("defendant")?,
+            }
+        }
+        "ChallengeAccepted" => {
+            let f = Fields::new("ChallengeAccepted", &event.fields);
+            AuditEvent::ChallengeAccepted { id: f.u32("id")? }
+        }
+        "JuryVoted" => {
+            let f = Fields::new("JuryVoted", &event.fields);
+            AuditEvent::JuryVoted {
+                id: f.u32("id")?,
+                juror: f.account("juror")?,
+                winner: f.variant("winner")?,
+            }
+        }
+        "ChallengeResolved" => {
+            let f = Fields::new("ChallengeResolved", &event.fields);
+            AuditEvent::ChallengeResolved {
+                id: f.u32("id")?,
+                winner: f.variant("winner")?,
+                rated: f.bool("rated")?,
+            }
+        }
+        "StakeChanged" => {
+            let f = Fields::new("StakeChanged", &event.fields);
+            AuditEvent::StakeChanged {
+                who: f.account("who")?,
+                stake: f.balance("stake")?,
+            }
+        }
+        "Endorsed" => {
+            let f = Fields::new("Endorsed", &event.fields);
+            AuditEvent::Endorsed {
+                endorser: f.account("endorser")?,
+                auditor: f.account("auditor")?,
+                amount: f.balance("amount")?,
+            }
+        }
+        "EndorsementWithdrawn" => {
+            let f = Fields::new("EndorsementWithdrawn", &event.fields);
+            AuditEvent::EndorsementWithdrawn {
+                endorser: f.account("endorser")?,
+                auditor: f.account("auditor")?,
+                amount: f.balance("amount")?,
+            }
+        }
+        "EndorsementsSlashed" => {
+            let f = Fields::new("EndorsementsSlashed", &event.fields);
+            AuditEvent::EndorsementsSlashed {
+                auditor: f.account("auditor")?,
+                winner: f.account("winner")?,
+                amount: f.balance("amount")?,
+            }
+        }
+        "FeeDistributed" => {
+            let f = Fields::new("FeeDistributed", &event.fields);
+            AuditEvent::FeeDistributed {
+                auditor: f.account("auditor")?,
+                fee: f.balance("fee")?,
+                endorser_share: f.balance("endorser_share")?,
+            }
+        }
+        "TeamCreated" => {
+            let f = Fields::new("TeamCreated", &event.fields);
+            AuditEvent::TeamCreated {
+                team: f.u32("team")?,
+                admin: f.account("admin")?,
+            }
+        }
+        "TeamInviteSent" => {
+            let f = Fields::new("TeamInviteSent", &event.fields);
+            AuditEvent::TeamInviteSent {
+                team: f.u32("team")?,
+                who: f.account("who")?,
+                shares: f.u32("shares")?,
+            }
+        }
+        "TeamJoined" => {
+            let f = Fields::new("TeamJoined", &event.fields);
+            AuditEvent::TeamJoined {
+                team: f.u32("team")?,
+                who: f.account("who")?,
+            }
+        }
+        "TeamLeft" => {
+            let f = Fields::new("TeamLeft", &event.fields);
+            AuditEvent::TeamLeft {
+                team: f.u32("team")?,
+                who: f.account("who")?,
+            }
+        }
+        "TeamDissolved" => {
+            let f = Fields::new("TeamDissolved", &event.fields);
+            AuditEvent::TeamDissolved {
+                team: f.u32("team")?,
+            }
+        }
+        "TeamSharesSet" => {
+            let f = Fields::new("TeamSharesSet", &event.fields);
+            AuditEvent::TeamSharesSet {
+                team: f.u32("team")?,
+                who: f.account("who")?,
+                shares: f.u32("shares")?,
+            }
+        }
+        "TeamFeeDistributed" => {
+            let f = Fields::new("TeamFeeDistributed", &event.fields);
+            AuditEvent::TeamFeeDistributed {
+                team: f.u32("team")?,
+                fee: f.balance("fee")?,
+            }
+        }
+        "PrivilegeThresholdSet" => {
+            let f = Fields::new("PrivilegeThresholdSet", &event.fields);
+            AuditEvent::PrivilegeThresholdSet {
+                privilege: f.variant("privilege")?,
+                threshold: f.optional("threshold", |f, data| f.u32_of("threshold", data))?,
+            }
+        }
+        _ => {
+            return Err(Error::UnknownEvent {
+                pallet: AUDIT_MODULE,
+                event: name.to_string(),
+            })
+        }
+    })
+}
+
+/// Named fields of an event, every getter fails with `Error::InvalidField` if the field is missing or has another type
+struct Fields<'a> {
+    event: &'static str,
+    fields: &'a [FieldData],
+}
+
+impl<'a> Fields<'a> {
+    fn new(event: &'static str, fields: &'a [FieldData]) -> Self {
+        Fields { event, fields }
+    }
+
+    fn invalid(&self, field: &'static str) -> Error {
+        Error::InvalidField {
+            event: self.event,
+            field,
+        }
+    }
+
+    fn get(&self, field: &'static str) -> Result<&'a ParsedData, Error> {
+        self.fields
+            .iter()
+            .find(|f| f.field_name.as_deref() == Some(field))
+            .map(|f| &f.data.data)
+            .ok_or_else(|| self.invalid(field))
+    }
+
+    fn account(&self, field: &'static str) -> Result<AccountId, Error> {
+        match self.get(field)? {
+            ParsedData::Id(account) => Ok(account.clone()),
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn hash(&self, field: &'static str) -> Result<H256, Error> {
+        self.hash_of(field, self.get(field)?)
+    }
+
+    fn hash_of(&self, field: &'static str, data: &ParsedData) -> Result<H256, Error> {
+        match data {
+            ParsedData::H256(hash) => Ok(*hash),
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn u32(&self, field: &'static str) -> Result<u32, Error> {
+        self.u32_of(field, self.get(field)?)
+    }
+
+    fn u32_of(&self, field: &'static str, data: &ParsedData) -> Result<u32, Error> {
+        match data {
+            ParsedData::PrimitiveU32 { value, .. } => Ok(*value),
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn balance(&self, field: &'static str) -> Result<Balance, Error> {
+        match self.get(field)? {
+            ParsedData::PrimitiveU128 { value, .. } => Ok(*value),
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn bool(&self, field: &'static str) -> Result<bool, Error> {
+        match self.get(field)? {
+            ParsedData::PrimitiveBool(value) => Ok(*value),
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn bytes(&self, field: &'static str) -> Result<Vec<u8>, Error> {
+        match self.get(field)? {
+            ParsedData::Sequence(sequence) => match &sequence.data {
+                Sequence::U8(bytes) => Ok(bytes.clone()),
+                _ => Err(self.invalid(field)),
+            },
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn variant<E: FromVariantName>(&self, field: &'static str) -> Result<E, Error> {
+        self.variant_of(field, self.get(field)?)
+    }
+
+    fn variant_of<E: FromVariantName>(
+        &self,
+        field: &'static str,
+        data: &ParsedData,
+    ) -> Result<E, Error> {
+        match data {
+            ParsedData::Variant(variant) => {
+                E::from_variant_name(&variant.variant_name).ok_or_else(|| self.invalid(field))
+            }
+            _ => Err(self.invalid(field)),
+        }
+    }
+
+    fn optional<V>(
+        &self,
+        field: &'static str,
+        decode: impl FnOnce(&Self, &ParsedData) -> Result<V, Error>,
+    ) -> Result<Option<V>, Error> {
+        match self.get(field)? {
+            ParsedData::Option(None) => Ok(None),
+            ParsedData::Option(Some(data)) => decode(self, data).map(Some),
+            _ => Err(self.invalid(field)),
+        }
+    }
+}
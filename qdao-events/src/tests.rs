@@ -0,0 +1,508 @@
+use super::*;
+
+use std::{fs, path::Path};
+
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::{MetaType, Registry, TypeInfo};
+
+/// Mirrors of the runtime types, encoded the same way as the node encodes `System::Events`. Not every variant is
+/// used, but all of them are part of the type information.
+#[allow(dead_code)]
+mod runtime {
+    use parity_scale_codec::Encode;
+    use scale_info::TypeInfo;
+    use sp_core::{crypto::AccountId32, H256};
+
+    #[derive(Encode, TypeInfo)]
+    pub enum Phase {
+        ApplyExtrinsic(u32),
+        Finalization,
+        Initialization,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub struct EventRecord {
+        pub phase: Phase,
+        pub event: RuntimeEvent,
+        pub topics: Vec<H256>,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum RuntimeEvent {
+        System(SystemEvent),
+        ExoSys(ExoSysEvent),
+        AuditModule(AuditEvent),
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum SystemEvent {
+        NewAccount { account: AccountId32 },
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum ExoSysEvent {
+        ExecutionRequest {
+            who: AccountId32,
+            url: Vec<u8>,
+            hash: H256,
+        },
+        ReviewCompleted {
+            who: AccountId32,
+            hash: H256,
+            result: u32,
+        },
+        Unknown,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum Winner {
+        Player0,
+        Player1,
+        Draw,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum Domain {
+        Substrate,
+        Ink,
+        Solidity,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum Severity {
+        Low,
+        Medium,
+        High,
+        Critical,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum Privilege {
+        ClaimReview,
+        JuryDuty,
+        ApproveAuditors,
+        ProposeToolChange,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    pub enum AuditEvent {
+        GameResult {
+            player0: AccountId32,
+            player1: AccountId32,
+            winner: Winner,
+            domain: Option<Domain>,
+            review_hash: Option<H256>,
+            severity: Severity,
+        },
+        ChallengeResolved {
+            id: u32,
+            winner: Winner,
+            rated: bool,
+        },
+        Endorsed {
+            endorser: AccountId32,
+            auditor: AccountId32,
+            amount: u128,
+        },
+        PrivilegeThresholdSet {
+            privilege: Privilege,
+            threshold: Option<u32>,
+        },
+    }
+}
+
+fn record(event: runtime::RuntimeEvent) -> runtime::EventRecord {
+    runtime::EventRecord {
+        phase: runtime::Phase::ApplyExtrinsic(1),
+        event,
+        topics: Vec::new(),
+    }
+}
+
+fn decode<T: Encode + TypeInfo + 'static>(value: &T) -> Result<Vec<QdaoEvent>, Error> {
+    let mut registry = Registry::new();
+    let ty = registry.register_type(&MetaType::new::<T>());
+    let registry: PortableRegistry = registry.into();
+
+    decode_events_as_type(&ty, &value.encode(), &registry)
+}
+
+fn account(seed: u8) -> AccountId32 {
+    AccountId32::new([seed; 32])
+}
+
+/// Reads a fixture recorded from a development node by `scripts/record-fixtures.sh`
+fn fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    let data = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Fixture {} can't be read: {}", path.display(), e));
+    hex::decode(data.trim().trim_start_matches("0x")).expect("Fixture is not hex")
+}
+
+fn recorded_metadata() -> RuntimeMetadataV14 {
+    match RuntimeMetadataPrefixed::decode(&mut &fixture("metadata.hex")[..])
+        .expect("Invalid metadata")
+        .1
+    {
+        RuntimeMetadata::V14(metadata) => metadata,
+        _ => panic!("Recorded metadata is not V14"),
+    }
+}
+
+#[test]
+fn exo_sys_events_are_decoded() {
+    // Given
+    let records = vec![
+        record(runtime::RuntimeEvent::ExoSys(
+            runtime::ExoSysEvent::ExecutionRequest {
+                who: account(1),
+                url: b"https://example.com/tool.tar".to_vec(),
+                hash: H256::repeat_byte(2),
+            },
+        )),
+        record(runtime::RuntimeEvent::ExoSys(
+            runtime::ExoSysEvent::ReviewCompleted {
+                who: account(3),
+                hash: H256::repeat_byte(2),
+                result: 7,
+            },
+        )),
+    ];
+
+    // When
+    let events = decode(&records);
+
+    // Then
+    assert_eq!(
+        events,
+        Ok(vec![
+            QdaoEvent::ExoSys(ExoSysEvent::ExecutionRequest {
+                who: account(1),
+                url: b"https://example.com/tool.tar".to_vec(),
+                hash: H256::repeat_byte(2),
+            }),
+            QdaoEvent::ExoSys(ExoSysEvent::ReviewCompleted {
+                who: account(3),
+                hash: H256::repeat_byte(2),
+                result: 7,
+            }),
+        ])
+    );
+}
+
+#[test]
+fn audit_events_are_decoded() {
+    // Given
+    let records = vec![
+        record(runtime::RuntimeEvent::AuditModule(
+            runtime::AuditEvent::GameResult {
+                player0: account(1),
+                player1: account(2),
+                winner: runtime::Winner::Player1,
+                domain: Some(runtime::Domain::Ink),
+                review_hash: None,
+                severity: runtime::Severity::High,
+            },
+        )),
+        record(runtime::RuntimeEvent::AuditModule(
+            runtime::AuditEvent::ChallengeResolved {
+                id: 4,
+                winner: runtime::Winner::Draw,
+                rated: true,
+            },
+        )),
+        record(runtime::RuntimeEvent::AuditModule(
+            runtime::AuditEvent::Endorsed {
+                endorser: account(5),
+                auditor: account(1),
+                amount: 1_000_000_000_000_000_000_000,
+            },
+        )),
+        record(runtime::RuntimeEvent::AuditModule(
+            runtime::AuditEvent::PrivilegeThresholdSet {
+                privilege: runtime::Privilege::JuryDuty,
+                threshold: Some(1_500),
+            },
+        )),
+    ];
+
+    // When
+    let events = decode(&records);
+
+    // Then
+    assert_eq!(
+        events,
+        Ok(vec![
+            QdaoEvent::AuditModule(AuditEvent::GameResult {
+                player0: account(1),
+                player1: account(2),
+                winner: Winner::Player1,
+                domain: Some(Domain::Ink),
+                review_hash: None,
+                severity: Severity::High,
+            }),
+            QdaoEvent::AuditModule(AuditEvent::ChallengeResolved {
+                id: 4,
+                winner: Winner::Draw,
+                rated: true,
+            }),
+            QdaoEvent::AuditModule(AuditEvent::Endorsed {
+                endorser: account(5),
+                auditor: account(1),
+                amount: 1_000_000_000_000_000_000_000,
+            }),
+            QdaoEvent::AuditModule(AuditEvent::PrivilegeThresholdSet {
+                privilege: Privilege::JuryDuty,
+                threshold: Some(1_500),
+            }),
+        ])
+    );
+}
+
+#[test]
+fn events_of_other_pallets_are_skipped() {
+    // Given
+    let records = vec![
+        record(runtime::RuntimeEvent::System(
+            runtime::SystemEvent::NewAccount {
+                account: account(1),
+            },
+        )),
+        record(runtime::RuntimeEvent::AuditModule(
+            runtime::AuditEvent::ChallengeResolved {
+                id: 1,
+                winner: runtime::Winner::Player0,
+                rated: false,
+            },
+        )),
+    ];
+
+    // When
+    let events = decode(&records);
+
+    // Then
+    assert_eq!(
+        events,
+        Ok(vec![QdaoEvent::AuditModule(
+            AuditEvent::ChallengeResolved {
+                id: 1,
+                winner: Winner::Player0,
+                rated: false,
+            }
+        )])
+    );
+}
+
+#[test]
+fn unknown_events_are_reported() {
+    // Given
+    let records = vec![record(runtime::RuntimeEvent::ExoSys(
+        runtime::ExoSysEvent::Unknown,
+    ))];
+
+    // When
+    let events = decode(&records);
+
+    // Then
+    assert_eq!(
+        events,
+        Err(Error::UnknownEvent {
+            pallet: EXO_SYS,
+            event: "Unknown".to_string(),
+        })
+    );
+}
+
+#[test]
+fn truncated_events_fail_to_decode() {
+    // Given
+    let records = vec![record(runtime::RuntimeEvent::ExoSys(
+        runtime::ExoSysEvent::ReviewCompleted {
+            who: account(1),
+            hash: H256::repeat_byte(1),
+            result: 0,
+        },
+    ))];
+    let mut registry = Registry::new();
+    let ty = registry.register_type(&MetaType::new::<Vec<runtime::EventRecord>>());
+    let registry: PortableRegistry = registry.into();
+    let data = records.encode();
+
+    // When
+    let events = decode_events_as_type(&ty, &data[..data.len() - 1], &registry);
+
+    // Then
+    assert!(matches!(events, Err(Error::Parser(_))));
+}
+
+#[test]
+fn generated_events_are_decoded_statically() {
+    use crate::generated::runtime_types::{
+        frame_support::dispatch::{DispatchClass, DispatchInfo, Pays},
+        frame_system::{self, EventRecord, Phase},
+        qdao_audit_pallet, qdao_exo_pallet,
+        qdao_runtime::RuntimeEvent,
+        sp_weights::weight_v2::Weight,
+    };
+
+    // Given
+    let record = |event| EventRecord {
+        phase: Phase::ApplyExtrinsic(1),
+        event,
+        topics: Vec::new(),
+    };
+    let records = vec![
+        record(RuntimeEvent::System(
+            frame_system::pallet::Event::ExtrinsicSuccess {
+                dispatch_info: DispatchInfo {
+                    weight: Weight { ref_time: 1_000 },
+                    class: DispatchClass::Normal,
+                    pays_fee: Pays::Yes,
+                },
+            },
+        )),
+        record(RuntimeEvent::ExoSys(
+            qdao_exo_pallet::pallet::Event::ExecutionRequest {
+                who: account(1),
+                url: b"https://example.com/tool.tar".to_vec(),
+                hash: H256::repeat_byte(2),
+            },
+        )),
+        record(RuntimeEvent::AuditModule(
+            qdao_audit_pallet::pallet::Event::JuryVoted {
+                id: 3,
+                juror: account(4),
+                winner: qdao_audit_pallet::pallet::Winner::Player0,
+            },
+        )),
+    ];
+
+    // When
+    let events = decode_events_static(&records.encode());
+
+    // Then
+    assert_eq!(
+        events,
+        Ok(vec![
+            QdaoEvent::ExoSys(ExoSysEvent::ExecutionRequest {
+                who: account(1),
+                url: b"https://example.com/tool.tar".to_vec(),
+                hash: H256::repeat_byte(2),
+            }),
+            QdaoEvent::AuditModule(AuditEvent::JuryVoted {
+                id: 3,
+                juror: account(4),
+                winner: Winner::Player0,
+            }),
+        ])
+    );
+}
+
+#[test]
+fn truncated_events_fail_to_decode_statically() {
+    // Given
+    let records: crate::generated::EventRecords = Vec::new();
+    let mut data = records.encode();
+    data.push(0);
+
+    // When
+    let events = decode_events_static(&data);
+
+    // Then
+    assert!(matches!(events, Err(Error::Codec(_))));
+}
+
+#[test]
+fn types_are_generated_from_the_registry() {
+    // Given
+    let mut registry = Registry::new();
+    let ty = registry.register_type(&MetaType::new::<Vec<runtime::EventRecord>>());
+    let registry: PortableRegistry = registry.into();
+
+    // When
+    let code = codegen::generate_for_type(ty.id(), &registry).unwrap();
+
+    // Then
+    let runtime = "runtime_types::qdao_events::tests::runtime";
+    assert!(code.contains(&format!(
+        "pub type EventRecords = ::std::vec::Vec<{}::EventRecord>;",
+        runtime
+    )));
+    assert!(code.contains(
+        "pub enum ExoSysEvent {\n\
+         #[codec(index = 0)]\n\
+         ExecutionRequest {\n\
+         who: ::sp_core::crypto::AccountId32,\n\
+         url: ::std::vec::Vec<u8>,\n\
+         hash: ::sp_core::H256,\n\
+         },\n"
+    ));
+    assert!(code.contains(&format!(
+        "domain: ::core::option::Option<{}::Domain>,\n",
+        runtime
+    )));
+    assert!(code.contains(&format!(
+        "pub struct EventRecord {{\n\
+         pub phase: {0}::Phase,\n\
+         pub event: {0}::RuntimeEvent,\n\
+         pub topics: ::std::vec::Vec<::sp_core::H256>,\n\
+         }}\n",
+        runtime
+    )));
+}
+
+#[test]
+#[ignore = "needs fixtures recorded from a development node with scripts/record-fixtures.sh"]
+fn recorded_events_are_decoded() {
+    // Given
+    let metadata = recorded_metadata();
+    let data = fixture("events.hex");
+
+    // When
+    let events = decode_events(&metadata, &data).unwrap();
+    let static_events = decode_events_static(&data).unwrap();
+
+    // Then
+    assert!(events.iter().any(|event| matches!(
+        event,
+        QdaoEvent::ExoSys(ExoSysEvent::ExecutionRequest { .. })
+    )));
+    assert_eq!(events, static_events);
+}
+
+#[test]
+#[ignore = "needs fixtures recorded from a development node with scripts/record-fixtures.sh"]
+fn generated_types_match_recorded_metadata() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    // Given
+    let metadata = recorded_metadata();
+
+    // When
+    let code = codegen::generate(&metadata).unwrap();
+    let mut rustfmt = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("rustfmt could not be started");
+    rustfmt
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(code.as_bytes())
+        .unwrap();
+    let output = rustfmt.wait_with_output().unwrap();
+
+    // Then
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout).unwrap() == include_str!("generated.rs"),
+        "src/generated.rs is outdated, regenerate it from tests/fixtures/metadata.hex"
+    );
+}
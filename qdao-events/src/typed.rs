@@ -0,0 +1,206 @@
+//! Static decoding of the events with the types in `generated.rs`
+//!
+//! The events are converted with exhaustive matches, so a changed event of `ExoSys` or `AuditModule` becomes a compile
+//! error once the types are regenerated, instead of a decoding error at runtime.
+
+use parity_scale_codec::DecodeAll;
+
+use crate::generated::{runtime_types, EventRecords};
+use crate::{AuditEvent, Domain, Error, ExoSysEvent, Privilege, QdaoEvent, Severity, Winner};
+
+use runtime_types::qdao_audit_pallet::pallet as audit;
+use runtime_types::qdao_exo_pallet::pallet as exo;
+use runtime_types::qdao_runtime::RuntimeEvent;
+
+/// Decodes the QDAO events of a block from the raw value of the `System::Events` storage entry, without metadata.
+/// Only works for the runtime the types were generated from.
+pub fn decode_events_static(data: &[u8]) -> Result<Vec<QdaoEvent>, Error> {
+    let records =
+        EventRecords::decode_all(&mut &data[..]).map_err(|e| Error::Codec(e.to_string()))?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::ExoSys(event) => Some(QdaoEvent::ExoSys(event.into())),
+            RuntimeEvent::AuditModule(event) => Some(QdaoEvent::AuditModule(event.into())),
+            _ => None,
+        })
+        .collect())
+}
+
+impl From<exo::Event> for ExoSysEvent {
+    fn from(event: exo::Event) -> Self {
+        match event {
+            exo::Event::ExecutionRequest { who, url, hash } => {
+                ExoSysEvent::ExecutionRequest { who, url, hash }
+            }
+            exo::Event::ExecutionFinish {
+                ret_hash,
+                ret_result,
+            } => ExoSysEvent::ExecutionFinish {
+                ret_hash,
+                ret_result,
+            },
+            exo::Event::ReviewClaimed { who, hash } => ExoSysEvent::ReviewClaimed { who, hash },
+            exo::Event::ReviewClaimedByTeam { who, team, hash } => {
+                ExoSysEvent::ReviewClaimedByTeam { who, team, hash }
+            }
+            exo::Event::ReviewUnclaimed { who, hash } => ExoSysEvent::ReviewUnclaimed { who, hash },
+            exo::Event::ReviewCompleted { who, hash, result } => {
+                ExoSysEvent::ReviewCompleted { who, hash, result }
+            }
+        }
+    }
+}
+
+impl From<audit::Event> for AuditEvent {
+    fn from(event: audit::Event) -> Self {
+        match event {
+            audit::Event::SignedUp { who } => AuditEvent::SignedUp { who },
+            audit::Event::GameResult {
+                player0,
+                player1,
+                winner,
+                domain,
+                review_hash,
+                severity,
+            } => AuditEvent::GameResult {
+                player0,
+                player1,
+                winner: winner.into(),
+                domain: domain.map(Into::into),
+                review_hash,
+                severity: severity.into(),
+            },
+            audit::Event::AccountCancelled { who, unlock_at } => {
+                AuditEvent::AccountCancelled { who, unlock_at }
+            }
+            audit::Event::Withdrawn { who } => AuditEvent::Withdrawn { who },
+            audit::Event::ApprovalRevoked { approver, approvee } => {
+                AuditEvent::ApprovalRevoked { approver, approvee }
+            }
+            audit::Event::ApplicationExpired { who } => AuditEvent::ApplicationExpired { who },
+            audit::Event::ChallengeOpened {
+                id,
+                challenger,
+                defendant,
+            } => AuditEvent::ChallengeOpened {
+                id,
+                challenger,
+                defendant,
+            },
+            audit::Event::ChallengeAccepted { id } => AuditEvent::ChallengeAccepted { id },
+            audit::Event::JuryVoted { id, juror, winner } => AuditEvent::JuryVoted {
+                id,
+                juror,
+                winner: winner.into(),
+            },
+            audit::Event::ChallengeResolved { id, winner, rated } => {
+                AuditEvent::ChallengeResolved {
+                    id,
+                    winner: winner.into(),
+                    rated,
+                }
+            }
+            audit::Event::StakeChanged { who, stake } => AuditEvent::StakeChanged { who, stake },
+            audit::Event::Endorsed {
+                endorser,
+                auditor,
+                amount,
+            } => AuditEvent::Endorsed {
+                endorser,
+                auditor,
+                amount,
+            },
+            audit::Event::EndorsementWithdrawn {
+                endorser,
+                auditor,
+                amount,
+            } => AuditEvent::EndorsementWithdrawn {
+                endorser,
+                auditor,
+                amount,
+            },
+            audit::Event::EndorsementsSlashed {
+                auditor,
+                winner,
+                amount,
+            } => AuditEvent::EndorsementsSlashed {
+                auditor,
+                winner,
+                amount,
+            },
+            audit::Event::FeeDistributed {
+                auditor,
+                fee,
+                endorser_share,
+            } => AuditEvent::FeeDistributed {
+                auditor,
+                fee,
+                endorser_share,
+            },
+            audit::Event::TeamCreated { team, admin } => AuditEvent::TeamCreated { team, admin },
+            audit::Event::TeamInviteSent { team, who, shares } => {
+                AuditEvent::TeamInviteSent { team, who, shares }
+            }
+            audit::Event::TeamJoined { team, who } => AuditEvent::TeamJoined { team, who },
+            audit::Event::TeamLeft { team, who } => AuditEvent::TeamLeft { team, who },
+            audit::Event::TeamDissolved { team } => AuditEvent::TeamDissolved { team },
+            audit::Event::TeamSharesSet { team, who, shares } => {
+                AuditEvent::TeamSharesSet { team, who, shares }
+            }
+            audit::Event::TeamFeeDistributed { team, fee } => {
+                AuditEvent::TeamFeeDistributed { team, fee }
+            }
+            audit::Event::PrivilegeThresholdSet {
+                privilege,
+                threshold,
+            } => AuditEvent::PrivilegeThresholdSet {
+                privilege: privilege.into(),
+                threshold,
+            },
+        }
+    }
+}
+
+impl From<audit::Winner> for Winner {
+    fn from(winner: audit::Winner) -> Self {
+        match winner {
+            audit::Winner::Player0 => Winner::Player0,
+            audit::Winner::Player1 => Winner::Player1,
+            audit::Winner::Draw => Winner::Draw,
+        }
+    }
+}
+
+impl From<audit::Domain> for Domain {
+    fn from(domain: audit::Domain) -> Self {
+        match domain {
+            audit::Domain::Substrate => Domain::Substrate,
+            audit::Domain::Ink => Domain::Ink,
+            audit::Domain::Solidity => Domain::Solidity,
+        }
+    }
+}
+
+impl From<audit::Severity> for Severity {
+    fn from(severity: audit::Severity) -> Self {
+        match severity {
+            audit::Severity::Low => Severity::Low,
+            audit::Severity::Medium => Severity::Medium,
+            audit::Severity::High => Severity::High,
+            audit::Severity::Critical => Severity::Critical,
+        }
+    }
+}
+
+impl From<audit::Privilege> for Privilege {
+    fn from(privilege: audit::Privilege) -> Self {
+        match privilege {
+            audit::Privilege::ClaimReview => Privilege::ClaimReview,
+            audit::Privilege::JuryDuty => Privilege::JuryDuty,
+            audit::Privilege::ApproveAuditors => Privilege::ApproveAuditors,
+            audit::Privilege::ProposeToolChange => Privilege::ProposeToolChange,
+        }
+    }
+}
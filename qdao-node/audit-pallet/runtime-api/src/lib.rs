@@ -0,0 +1,33 @@
+//! Runtime API definition for `qdao-audit-pallet`
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use qdao_audit_pallet::{AuditorStatus, Domain, LeaderboardEntry};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Queries for auditors registered in `qdao-audit-pallet`
+    pub trait AuditApi<AccountId>
+    where
+        AccountId: Codec,
+    {
+        /// Returns auditors sorted by score in descending order
+        /// # Arguments
+        ///
+        /// * `status` - only auditors with this approval status are returned
+        ///
+        /// * `specialization` - if set, only auditors specialized in this domain are returned and ranked by their domain score
+        ///
+        /// * `offset` - number of auditors to skip
+        ///
+        /// * `limit` - maximum number of auditors to return
+        ///
+        fn leaderboard(
+            status: AuditorStatus,
+            specialization: Option<Domain>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<LeaderboardEntry<AccountId>>;
+    }
+}
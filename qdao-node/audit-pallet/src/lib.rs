@@ -22,6 +22,7 @@ mod tests;
 mod benchmarking;
 
 mod elo_comp;
+pub mod migrations;
 
 type DepositBalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
@@ -32,11 +33,42 @@ pub mod pallet {
 
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_support::{
+        sp_runtime::{
+            traits::{CheckedSub, Saturating, Zero},
+            Perbill,
+        },
+        traits::{BalanceStatus, ExistenceRequirement},
+        DefaultNoBound,
+    };
     use frame_system::pallet_prelude::*;
     use sp_core::H256;
+    use sp_std::fmt::Debug;
+
+    /// The current storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
-    #[derive(Encode, Decode, Default, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    #[derive(
+        Encode,
+        Decode,
+        DefaultNoBound,
+        CloneNoBound,
+        RuntimeDebugNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(RequiredApprovals, MaxSpecializations, MaxProfileLocatorLength))]
+    #[codec(mel_bound(Hash: MaxEncodedLen, AccountId: MaxEncodedLen))]
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            serialize = "Hash: serde::Serialize, AccountId: serde::Serialize",
+            deserialize = "Hash: serde::Deserialize<'de>, AccountId: serde::Deserialize<'de>"
+        ))
+    )]
     /// Holds the data which is associated to an auditor.
     /// # Fields
     ///
@@ -46,15 +78,179 @@ pub mod pallet {
     /// * `profile_hash` - A hash of the profile that the user submitted. Is supposed to be the hash of a markdown document which describes the user's
     ///                    background and qualification of being an auditor.
     ///
-    /// * `approved_by` - A user needs three approval's from already approved auditor's.Therefore this `BoundedVec<AccountId, ConstU32<3>>` can hold up
-    ///                   to three `AccountId`'s of approving auditors.
+    /// * `approved_by` - A user needs `RequiredApprovals` approval's from already approved auditor's. Therefore this
+    ///                   `BoundedVec<AccountId, RequiredApprovals>` can hold up to `RequiredApprovals` `AccountId`'s of approving auditors.
+    ///
+    /// * `specializations` - The domains the auditor declared to be specialized in, sorted and without duplicates.
+    ///
+    /// * `profile_locator` - Where the profile can be retrieved, e.g. an IPFS CID (`ipfs://<cid>`) or an URL. The BLAKE2-256 hash
+    ///                       of the retrieved document has to match `profile_hash`.
     ///
-    pub struct AuditorData<Hash, AccountId> {
+    pub struct AuditorData<
+        Hash,
+        AccountId,
+        RequiredApprovals,
+        MaxSpecializations,
+        MaxProfileLocatorLength,
+    >
+    where
+        Hash: Clone + PartialEq + Eq + Debug + Default,
+        AccountId: Clone + PartialEq + Eq + Debug,
+        RequiredApprovals: Get<u32>,
+        MaxSpecializations: Get<u32>,
+        MaxProfileLocatorLength: Get<u32>,
+    {
         pub score: Option<u32>,
         pub profile_hash: Hash,
-        pub approved_by: BoundedVec<AccountId, ConstU32<3>>,
+        pub approved_by: BoundedVec<AccountId, RequiredApprovals>,
+        pub specializations: BoundedVec<Domain, MaxSpecializations>,
+        pub profile_locator: Option<BoundedVec<u8, MaxProfileLocatorLength>>,
+    }
+
+    /// `AuditorData` as it is stored for the runtime configuration `T`
+    pub type AuditorDataOf<T> = AuditorData<
+        H256,
+        <T as frame_system::Config>::AccountId,
+        <T as Config>::RequiredApprovals,
+        <T as Config>::MaxSpecializations,
+        <T as Config>::MaxProfileLocatorLength,
+    >;
+
+    #[derive(
+        Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TypeInfo, MaxEncodedLen,
+    )]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    /// Domains in which auditors can specialize. Auditors have a separate reputation score per domain.
+    pub enum Domain {
+        /// Substrate pallets and runtimes
+        Substrate,
+        /// ink! smart contracts
+        Ink,
+        /// Solidity smart contracts
+        Solidity,
+    }
+
+    impl Domain {
+        /// All domains
+        pub const ALL: [Domain; 3] = [Domain::Substrate, Domain::Ink, Domain::Solidity];
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    /// Privileges which approved auditors receive once their score reaches the privilege's threshold
+    pub enum Privilege {
+        /// May claim manual reviews
+        ClaimReview,
+        /// May sit on dispute juries
+        JuryDuty,
+        /// May approve other auditors
+        ApproveAuditors,
+        /// May propose changes of the tool registry
+        ProposeToolChange,
+    }
+
+    impl Privilege {
+        /// All privileges
+        pub const ALL: [Privilege; 4] = [
+            Privilege::ClaimReview,
+            Privilege::JuryDuty,
+            Privilege::ApproveAuditors,
+            Privilege::ProposeToolChange,
+        ];
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    /// Severity of the finding which was missed by the challenged auditor
+    pub enum Severity {
+        Low,
+        Medium,
+        High,
+        Critical,
+    }
+
+    impl Severity {
+        /// The Elo K-factor for challenges about findings of this severity, i.e. the maximal rating change
+        pub fn k_factor(&self) -> i32 {
+            match self {
+                Severity::Low => 16,
+                Severity::Medium => 32,
+                Severity::High => 48,
+                Severity::Critical => 64,
+            }
+        }
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    /// Metadata of the challenge which a game result originates from
+    /// # Fields
+    ///
+    /// * `review_hash` - the hash of the disputed review, i.e. the key of its `ReviewRecord`. `None` if the result
+    ///                   is not linked to a review.
+    ///
+    /// * `severity` - the severity of the missed finding, by which the rating change is weighted
+    ///
+    /// * `domain` - the domain of the challenge, only the rating in this domain is affected. If `None`, the global
+    ///              rating is affected.
+    ///
+    pub struct ChallengeContext<Hash> {
+        pub review_hash: Option<Hash>,
+        pub severity: Severity,
+        pub domain: Option<Domain>,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    /// Approval status by which auditor queries can be filtered
+    pub enum AuditorStatus {
+        /// Approved and pending auditors
+        Any,
+        /// Auditors which received the auditor status
+        Approved,
+        /// Auditors whose approval is pending
+        Pending,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    /// Status of an auditor's on-chain identity, as judged by the registrars of the identity system
+    pub enum IdentityStatus {
+        /// The account has no identity
+        NotSet,
+        /// The identity is set, but no registrar judged it (yet)
+        Unjudged,
+        /// A registrar judged the identity as reasonable or known good
+        Verified,
+        /// A registrar judged the identity as low quality or erroneous
+        Rejected,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    /// An auditor as returned by the leaderboard query
+    /// # Fields
+    ///
+    /// * `account` - the account id of the auditor
+    ///
+    /// * `score` - the score by which the auditor is ranked. This is the domain score if the leaderboard is filtered by
+    ///             specialization, otherwise the global score. Pending auditors have a `score` of value `None`
+    ///
+    /// * `profile_hash` - the hash of the auditor's profile
+    ///
+    /// * `specializations` - the domains the auditor declared to be specialized in
+    ///
+    /// * `identity` - the judgement of the auditor's on-chain identity
+    ///
+    pub struct LeaderboardEntry<AccountId> {
+        pub account: AccountId,
+        pub score: Option<u32>,
+        pub profile_hash: H256,
+        pub specializations: Vec<Domain>,
+        pub identity: IdentityStatus,
     }
 
+    /// Maximum number of auditors which are returned by a single leaderboard query
+    pub const MAX_LEADERBOARD_PAGE_SIZE: u32 = 100;
+
     #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
     /// Denotes the result/winner after challenging an auditor
     pub enum Winner {
@@ -63,6 +259,104 @@ pub mod pallet {
         Draw,
     }
 
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    /// Phase of an open challenge
+    pub enum ChallengePhase {
+        /// The defendant has to accept the challenge by bonding, otherwise the challenger wins
+        AwaitingResponse,
+        /// Both parties bonded, the jury votes on the winner
+        Voting,
+    }
+
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        RuntimeDebugNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(MaxJurors))]
+    #[codec(mel_bound(
+        AccountId: MaxEncodedLen,
+        BlockNumber: MaxEncodedLen,
+        Hash: MaxEncodedLen
+    ))]
+    /// A challenge of an auditor's finding, which is resolved by a jury or by the oracle
+    /// # Fields
+    ///
+    /// * `challenger` - the auditor which opened the challenge, player 0 of the resulting game
+    ///
+    /// * `defendant` - the challenged auditor, player 1 of the resulting game
+    ///
+    /// * `context` - the disputed review, the severity of the missed finding and the domain of the challenge
+    ///
+    /// * `phase` - whether the challenge awaits the defendant's response or the jury votes
+    ///
+    /// * `deadline` - the last block in which the defendant can accept the challenge or the jury can vote
+    ///
+    /// * `votes` - the jurors and the winner they voted for, `Winner::Draw` is an abstention
+    ///
+    pub struct Challenge<AccountId, BlockNumber, Hash, MaxJurors>
+    where
+        AccountId: Clone + PartialEq + Eq + Debug,
+        BlockNumber: Clone + PartialEq + Eq + Debug,
+        Hash: Clone + PartialEq + Eq + Debug,
+        MaxJurors: Get<u32>,
+    {
+        pub challenger: AccountId,
+        pub defendant: AccountId,
+        pub context: ChallengeContext<Hash>,
+        pub phase: ChallengePhase,
+        pub deadline: BlockNumber,
+        pub votes: BoundedVec<(AccountId, Winner), MaxJurors>,
+    }
+
+    /// `Challenge` as it is stored for the runtime configuration `T`
+    pub type ChallengeOf<T> = Challenge<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        <T as frame_system::Config>::Hash,
+        <T as Config>::MaxJurors,
+    >;
+
+    /// Id of an audit team
+    pub type TeamId = u32;
+
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        RuntimeDebugNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(MaxTeamMembers))]
+    #[codec(mel_bound(AccountId: MaxEncodedLen))]
+    /// An audit firm whose members audit as a team
+    /// # Fields
+    ///
+    /// * `admin` - the auditor which created the team, manages the members and their shares
+    ///
+    /// * `members` - the auditors of the team and their shares of the review fees paid to the team
+    ///
+    pub struct Team<AccountId, MaxTeamMembers>
+    where
+        AccountId: Clone + PartialEq + Eq + Debug,
+        MaxTeamMembers: Get<u32>,
+    {
+        pub admin: AccountId,
+        pub members: BoundedVec<(AccountId, u32), MaxTeamMembers>,
+    }
+
+    /// `Team` as it is stored for the runtime configuration `T`
+    pub type TeamOf<T> =
+        Team<<T as frame_system::Config>::AccountId, <T as Config>::MaxTeamMembers>;
+
     #[pallet::config]
     /// The `qdao-audit-pallet` Config trait implementation
     pub trait Config: frame_system::Config {
@@ -84,12 +378,115 @@ pub mod pallet {
         type InitialAuditorScore: Get<u32>;
 
         #[pallet::constant]
-        /// Minimal score which allows auditors to approve other auditors
+        /// Minimal score which allows auditors to approve other auditors. It is also the score threshold of all
+        /// privileges for which root did not set a threshold.
         type MinimalApproverScore: Get<u32>;
+
+        #[pallet::constant]
+        /// Number of approvals by approved auditors which are required to receive the auditor status
+        type RequiredApprovals: Get<u32>;
+
+        #[pallet::constant]
+        /// If `true`, approvals are weighted by the approvers' scores. The auditor status is granted as soon as the scores
+        /// of all approvers add up to `RequiredApprovals * MinimalApproverScore`, so fewer approvals of highly rated auditors suffice.
+        type ScoreWeightedApprovals: Get<bool>;
+
+        #[pallet::constant]
+        /// Number of blocks an auditor has to wait after cancelling their account before the stake can be withdrawn
+        type UnbondingPeriod: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Number of blocks after sign up within which an auditor has to receive the auditor status, otherwise the
+        /// application expires
+        type ApplicationExpiry: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Maximum number of domains an auditor can declare to be specialized in
+        type MaxSpecializations: Get<u32>;
+
+        #[pallet::constant]
+        /// Maximum length of the locator of an auditor profile
+        type MaxProfileLocatorLength: Get<u32>;
+
+        /// Provides the judgement of an auditor's on-chain identity
+        type IdentityVerifier: IdentityVerifier<Self::AccountId>;
+
+        #[pallet::constant]
+        /// If `true`, only auditors with a verified identity can be approved
+        type RequireIdentity: Get<bool>;
+
+        #[pallet::constant]
+        /// Number of blocks which have to pass before the same two auditors can play another rated game
+        type PairCooldown: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Number of blocks which have to pass before an auditor can play another rated game against anyone
+        type AuditorCooldown: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Length of the period in blocks within which the number of rated games per auditor is limited.
+        /// A value of zero disables the limit.
+        type RatingPeriod: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Maximum number of rated games an auditor can play within a `RatingPeriod`
+        type MaxGamesPerPeriod: Get<u32>;
+
+        #[pallet::constant]
+        /// Amount which challenger and defendant bond. The loser's bond is transferred to the winner.
+        type ChallengeBond: Get<DepositBalanceOf<Self>>;
+
+        #[pallet::constant]
+        /// Number of blocks within which the defendant has to accept a challenge
+        type ResponsePeriod: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Number of blocks within which the jury can vote on an accepted challenge
+        type VotingPeriod: Get<Self::BlockNumber>;
+
+        #[pallet::constant]
+        /// Maximum number of jurors which can vote on a challenge
+        type MaxJurors: Get<u32>;
+
+        /// Origin which can resolve challenges directly, e.g. an oracle or a council
+        type OracleOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        #[pallet::constant]
+        /// Additional stake which is required for every privilege the auditor's score unlocks
+        type StakePerPrivilege: Get<DepositBalanceOf<Self>>;
+
+        #[pallet::constant]
+        /// Fraction of the total deposits of the reviews an auditor claimed, which has to be covered by stake
+        type ClaimedValueRatio: Get<Perbill>;
+
+        #[pallet::constant]
+        /// Maximum number of accounts which can endorse an auditor
+        type MaxEndorsers: Get<u32>;
+
+        #[pallet::constant]
+        /// Minimal amount an account has to bond behind an auditor
+        type MinEndorsement: Get<DepositBalanceOf<Self>>;
+
+        #[pallet::constant]
+        /// Fraction of an auditor's review fees which is shared with their endorsers
+        type EndorserFeeShare: Get<Perbill>;
+
+        #[pallet::constant]
+        /// Fraction of the endorsements which is slashed and paid to the winner if an auditor loses a challenge
+        type EndorsementSlash: Get<Perbill>;
+
+        #[pallet::constant]
+        /// Maximum number of auditors in a team
+        type MaxTeamMembers: Get<u32>;
+
+        #[pallet::constant]
+        /// Maximum number of pair cooldowns and team invitations which are removed per call when an auditor is removed
+        type MaxAuditorCleanup: Get<u32>;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
@@ -97,12 +494,116 @@ pub mod pallet {
     /// Storage for auditor scores
     /// If a new Auditor signed up whose approval is pending, the Auditor scrore will be None
     pub(super) type AuditorMap<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, AuditorData<sp_core::H256, T::AccountId>>;
+        StorageMap<_, Blake2_128Concat, T::AccountId, AuditorDataOf<T>>;
 
-    type AuditorMapData<T> = (
-        <T as frame_system::Config>::AccountId,
-        AuditorData<sp_core::H256, <T as frame_system::Config>::AccountId>,
-    );
+    #[pallet::storage]
+    #[pallet::getter(fn unbonding)]
+    /// Auditors which cancelled their account, mapped to the block number from which on their stake can be withdrawn.
+    /// As long as an auditor is unbonding, the account can neither approve nor be approved, but challenge results still apply.
+    pub(super) type Unbonding<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn application_deadline)]
+    /// Pending applications, mapped to the block number from which on the application can be expired.
+    /// The entry is removed as soon as the auditor status is granted or the account is cancelled.
+    pub(super) type ApplicationDeadline<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+    #[pallet::storage]
+    /// Reputation scores of auditors in a specific domain. As long as an auditor did not play a game in a domain,
+    /// there is no entry and the auditor's global score is used instead.
+    pub(super) type DomainScores<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, Domain, u32>;
+
+    #[pallet::storage]
+    /// Block number of the last rated game between two auditors. The game is stored for both orders of the pair, so
+    /// that all games of an auditor can be found by prefix.
+    pub(super) type LastPairGame<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        T::BlockNumber,
+    >;
+
+    #[pallet::storage]
+    /// Block number of the last rated game of an auditor
+    pub(super) type LastGame<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+    #[pallet::storage]
+    /// Number of rated games an auditor played in the current rating period, together with the index of the period
+    pub(super) type GamesInPeriod<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32)>;
+
+    #[pallet::storage]
+    /// Minimal score which is required for a privilege. If no threshold is set, `MinimalApproverScore` applies.
+    pub(super) type PrivilegeThresholds<T: Config> = StorageMap<_, Twox64Concat, Privilege, u32>;
+
+    #[pallet::storage]
+    /// Id which is assigned to the next challenge
+    pub(super) type NextChallengeId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn challenges)]
+    /// Open challenges by id. Resolved challenges are removed.
+    pub(super) type Challenges<T: Config> = StorageMap<_, Twox64Concat, u32, ChallengeOf<T>>;
+
+    #[pallet::storage]
+    /// Number of open challenges an auditor is party of. An auditor cannot withdraw as long as a challenge is open.
+    pub(super) type OpenChallenges<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn auditor_stake)]
+    /// Stake which is reserved for an auditor
+    pub(super) type AuditorStake<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, DepositBalanceOf<T>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn claimed_value)]
+    /// Total deposits of the reviews an auditor claimed and did not release yet
+    pub(super) type ClaimedValue<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, DepositBalanceOf<T>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn endorsements)]
+    /// Accounts which bonded funds behind an auditor, together with the bonded amount
+    pub(super) type Endorsements<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(T::AccountId, DepositBalanceOf<T>), T::MaxEndorsers>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    /// Id which is assigned to the next team
+    pub(super) type NextTeamId<T: Config> = StorageValue<_, TeamId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn teams)]
+    /// Audit teams by id, teams are removed when their last member leaves
+    pub(super) type Teams<T: Config> = StorageMap<_, Twox64Concat, TeamId, TeamOf<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn auditor_team)]
+    /// The team of an auditor, every auditor is member of at most one team
+    pub(super) type AuditorTeam<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, TeamId>;
+
+    #[pallet::storage]
+    /// Pending invitations of an auditor to a team together with the offered shares
+    pub(super) type TeamInvites<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, TeamId, u32>;
+
+    #[pallet::storage]
+    /// Removed auditors whose pair cooldowns and team invitations are not removed completely yet
+    pub(super) type PendingCleanup<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    type AuditorMapData<T> = (<T as frame_system::Config>::AccountId, AuditorDataOf<T>);
 
     #[pallet::genesis_config]
     /// Allows a Genesis config with pre-assigned Auditors
@@ -125,6 +626,20 @@ pub mod pallet {
             // <SingleValue<T>>::put(&self.single_value);
             for (a, b) in &self.auditor_map {
                 <AuditorMap<T>>::insert(a, b);
+                // Pending auditors expire like auditors which signed up in the genesis block
+                if b.score.is_none() {
+                    <ApplicationDeadline<T>>::insert(a, T::ApplicationExpiry::get());
+                }
+
+                let stake = Pallet::<T>::required_stake(a, Zero::zero());
+                if let Err(e) = T::Currency::reserve(a, stake) {
+                    panic!(
+                        "Invalid genesis config: auditor {:?} cannot reserve the required stake of {:?} ({:?}), \
+                         endow the account with enough funds",
+                        a, stake, e
+                    );
+                }
+                <AuditorStake<T>>::insert(a, stake);
             }
         }
     }
@@ -140,6 +655,103 @@ pub mod pallet {
             player0: T::AccountId,
             player1: T::AccountId,
             winner: Winner,
+            domain: Option<Domain>,
+            review_hash: Option<T::Hash>,
+            severity: Severity,
+        },
+        /// Auditor cancelled their account, the stake can be withdrawn from block `unlock_at` on
+        AccountCancelled {
+            who: T::AccountId,
+            unlock_at: T::BlockNumber,
+        },
+        /// Auditor withdrew their stake after the unbonding period, the account was removed
+        Withdrawn { who: T::AccountId },
+        /// An approver revoked their approval of a pending auditor
+        ApprovalRevoked {
+            approver: T::AccountId,
+            approvee: T::AccountId,
+        },
+        /// A pending application expired, the stake was returned and the account was removed
+        ApplicationExpired { who: T::AccountId },
+        /// An auditor challenged a finding of another auditor
+        ChallengeOpened {
+            id: u32,
+            challenger: T::AccountId,
+            defendant: T::AccountId,
+        },
+        /// The defendant accepted the challenge, the jury can vote from now on
+        ChallengeAccepted { id: u32 },
+        /// A juror voted on a challenge
+        JuryVoted {
+            id: u32,
+            juror: T::AccountId,
+            winner: Winner,
+        },
+        /// A challenge was resolved and the bonds were settled. `rated` is `false` if the scores were not updated,
+        /// because the result is a draw or one of the auditors is rate limited.
+        ChallengeResolved {
+            id: u32,
+            winner: Winner,
+            rated: bool,
+        },
+        /// The stake of an auditor was topped up or reduced, `stake` is the new total stake
+        StakeChanged {
+            who: T::AccountId,
+            stake: DepositBalanceOf<T>,
+        },
+        /// An account bonded funds behind an auditor, `amount` is the new total endorsement of the account
+        Endorsed {
+            endorser: T::AccountId,
+            auditor: T::AccountId,
+            amount: DepositBalanceOf<T>,
+        },
+        /// An account withdrew its endorsement of an auditor
+        EndorsementWithdrawn {
+            endorser: T::AccountId,
+            auditor: T::AccountId,
+            amount: DepositBalanceOf<T>,
+        },
+        /// The endorsements of an auditor which lost a challenge were slashed in favour of the winner
+        EndorsementsSlashed {
+            auditor: T::AccountId,
+            winner: T::AccountId,
+            amount: DepositBalanceOf<T>,
+        },
+        /// A review fee was paid to an auditor, `endorser_share` of the fee was paid to their endorsers
+        FeeDistributed {
+            auditor: T::AccountId,
+            fee: DepositBalanceOf<T>,
+            endorser_share: DepositBalanceOf<T>,
+        },
+        /// An auditor created a team
+        TeamCreated { team: TeamId, admin: T::AccountId },
+        /// The team admin invited an auditor to join the team with the given shares
+        TeamInviteSent {
+            team: TeamId,
+            who: T::AccountId,
+            shares: u32,
+        },
+        /// An auditor accepted an invitation and joined a team
+        TeamJoined { team: TeamId, who: T::AccountId },
+        /// An auditor left a team
+        TeamLeft { team: TeamId, who: T::AccountId },
+        /// The last member left the team, the team was removed
+        TeamDissolved { team: TeamId },
+        /// The team admin changed the shares of a member
+        TeamSharesSet {
+            team: TeamId,
+            who: T::AccountId,
+            shares: u32,
+        },
+        /// A review fee was split among the members of a team according to their shares
+        TeamFeeDistributed {
+            team: TeamId,
+            fee: DepositBalanceOf<T>,
+        },
+        /// The score threshold of a privilege was set, `None` if the default threshold applies again
+        PrivilegeThresholdSet {
+            privilege: Privilege,
+            threshold: Option<u32>,
         },
     }
 
@@ -152,7 +764,7 @@ pub mod pallet {
         StorageOverflow,
         /// Auditor is already signed up
         AlreadySignedUp,
-        /// Auditor doesn't provide enough stake for sign up
+        /// Auditor doesn't provide enough stake for sign up, their privileges or their claimed reviews
         InsufficientStake,
         /// User is not registered as an Auditor
         UnknownAuditor,
@@ -168,6 +780,88 @@ pub mod pallet {
         AlreadyApproved,
         /// Eloscore computational overflow (expected not to happen with Eloscore formula)
         UnexpectedEloOverflow,
+        /// Auditor cancelled their account and is waiting for the unbonding period to end
+        AuditorLeaving,
+        /// Auditor did not cancel their account, so there is nothing to withdraw
+        NotUnbonding,
+        /// The unbonding period of the auditor is not over yet
+        UnbondingPeriodNotOver,
+        /// The approvee did not receive an approval by the sender
+        NotApproved,
+        /// The user has no pending application
+        NoPendingApplication,
+        /// The application deadline is not reached yet
+        ApplicationNotExpired,
+        /// More specializations than `MaxSpecializations` were declared
+        TooManySpecializations,
+        /// A specialization was declared more than once
+        DuplicateSpecialization,
+        /// The profile locator is longer than `MaxProfileLocatorLength`
+        ProfileLocatorTooLong,
+        /// The profile locator is empty
+        EmptyProfileLocator,
+        /// The identity of the auditor was not verified by a registrar
+        IdentityNotVerified,
+        /// The two auditors played a rated game against each other recently
+        PairCooldown,
+        /// One of the auditors played a rated game recently
+        AuditorCooldown,
+        /// One of the auditors reached the maximum number of rated games in the current rating period
+        TooManyGames,
+        /// An auditor cannot challenge themselves
+        SelfChallenge,
+        /// There is no open challenge with the given id
+        UnknownChallenge,
+        /// Only the defendant can accept a challenge
+        NotDefendant,
+        /// The challenge is not in the phase which is required for this action
+        WrongChallengePhase,
+        /// The response period or the voting period of the challenge is over
+        ChallengeDeadlinePassed,
+        /// The response period or the voting period of the challenge is not over yet
+        ChallengeDeadlineNotPassed,
+        /// The juror's score is too low for jury duty
+        NotJuror,
+        /// Challenger and defendant cannot vote on their own challenge
+        PartyCannotVote,
+        /// The juror already voted on this challenge
+        AlreadyVoted,
+        /// The maximum number of jurors already voted on this challenge
+        TooManyVotes,
+        /// The auditor is party of an open challenge
+        OpenChallenge,
+        /// An auditor cannot endorse themselves, the stake can be topped up instead
+        SelfEndorsement,
+        /// The endorsement is lower than `MinEndorsement`
+        EndorsementTooLow,
+        /// The auditor already has `MaxEndorsers` endorsers
+        TooManyEndorsers,
+        /// The account does not endorse the auditor
+        NotEndorsed,
+        /// The auditor is already member of a team
+        AlreadyInTeam,
+        /// There is no team with the given id
+        UnknownTeam,
+        /// Only the team admin can manage the members of a team
+        NotTeamAdmin,
+        /// The auditor is not member of the team
+        NotTeamMember,
+        /// The auditor was not invited to the team
+        NoTeamInvite,
+        /// The team already has `MaxTeamMembers` members
+        TooManyTeamMembers,
+        /// Members need at least one share of the team's review fees
+        ZeroShares,
+        /// The admin can only leave the team as its last member
+        TeamAdminCannotLeave,
+        /// The auditor has to leave their team first
+        StillInTeam,
+        /// The auditor still has claimed reviews, which have to be released first
+        ClaimedReviews,
+        /// The data of the removed auditor account is not removed completely yet, see `clear_auditor_data`
+        CleanupPending,
+        /// There is no data of a removed auditor account left to remove
+        NothingToClean,
     }
 
     #[pallet::call]
@@ -178,7 +872,13 @@ pub mod pallet {
         ///
         /// * `profile_hash` - a hash auf the new auditors profile of type `H256`
         ///
-        pub fn sign_up(origin: OriginFor<T>, profile_hash: H256) -> DispatchResult {
+        /// * `profile_locator` - an optional IPFS CID or URL of type `Vec<u8>` where the profile can be retrieved
+        ///
+        pub fn sign_up(
+            origin: OriginFor<T>,
+            profile_hash: H256,
+            profile_locator: Option<Vec<u8>>,
+        ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
             // https://docs.substrate.io/v3/runtime/origins
@@ -189,16 +889,29 @@ pub mod pallet {
                 !AuditorMap::<T>::contains_key(&sender),
                 Error::<T>::AlreadySignedUp
             );
+            ensure!(
+                !<PendingCleanup<T>>::contains_key(&sender),
+                Error::<T>::CleanupPending
+            );
+
+            let profile_locator = Self::bound_profile_locator(profile_locator)?;
 
             T::Currency::reserve(&sender, T::MinAuditorStake::get())?;
+            <AuditorStake<T>>::insert(&sender, T::MinAuditorStake::get());
 
             // Register new Auditor
-            let auditor_data = AuditorData::<H256, T::AccountId> {
+            let auditor_data = AuditorDataOf::<T> {
                 score: None,
                 profile_hash,
-                approved_by: BoundedVec::with_bounded_capacity(3),
+                approved_by: BoundedVec::with_bounded_capacity(T::RequiredApprovals::get() as usize),
+                specializations: BoundedVec::default(),
+                profile_locator,
             };
             <AuditorMap<T>>::insert(sender.clone(), auditor_data);
+            <ApplicationDeadline<T>>::insert(
+                &sender,
+                <frame_system::Pallet<T>>::block_number() + T::ApplicationExpiry::get(),
+            );
 
             // Emit an event.
             Self::deposit_event(Event::SignedUp { who: sender });
@@ -207,18 +920,25 @@ pub mod pallet {
         }
 
         #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
-        /// An Auditor which signed up for auditor status can call this extrinsic to update their profile hash
+        /// An Auditor which signed up for auditor status can call this extrinsic to update their profile hash and locator
         /// # Arguments
         ///
         /// * `profile_hash` - a hash auf the new auditors profile of type `H256`
         ///
-        pub fn update_profile(origin: OriginFor<T>, profile_hash: H256) -> DispatchResult {
+        /// * `profile_locator` - an optional IPFS CID or URL of type `Vec<u8>` where the profile can be retrieved
+        ///
+        pub fn update_profile(
+            origin: OriginFor<T>,
+            profile_hash: H256,
+            profile_locator: Option<Vec<u8>>,
+        ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
             let mut auditor_data_to_update =
                 <AuditorMap<T>>::try_get(&sender).map_err(|_| Error::<T>::UnknownAuditor)?;
 
             auditor_data_to_update.profile_hash = profile_hash;
+            auditor_data_to_update.profile_locator = Self::bound_profile_locator(profile_locator)?;
 
             <AuditorMap<T>>::insert(sender, auditor_data_to_update);
 
@@ -226,9 +946,39 @@ pub mod pallet {
         }
 
         #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
+        /// An Auditor which signed up for auditor status can call this extrinsic to declare the domains they are specialized in
+        /// # Arguments
+        ///
+        /// * `specializations` - the domains of type `Vec<Domain>`, which replace the previously declared specializations
+        ///
+        pub fn update_specializations(
+            origin: OriginFor<T>,
+            mut specializations: Vec<Domain>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut auditor_data_to_update =
+                <AuditorMap<T>>::try_get(&sender).map_err(|_| Error::<T>::UnknownAuditor)?;
+
+            // Store specializations sorted, so that duplicates can be detected
+            specializations.sort();
+            ensure!(
+                specializations.windows(2).all(|pair| pair[0] != pair[1]),
+                Error::<T>::DuplicateSpecialization
+            );
+            auditor_data_to_update.specializations = specializations
+                .try_into()
+                .map_err(|_| Error::<T>::TooManySpecializations)?;
+
+            <AuditorMap<T>>::insert(sender, auditor_data_to_update);
 
-        /// Is called by an auditor which signed up for auditor status to cancel their
-        /// account and to unreserve the associated funds.
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
+        /// Is called by an auditor which signed up for auditor status to cancel their account. The account is marked as
+        /// leaving and the associated funds stay reserved until the `UnbondingPeriod` is over, so that pending challenges
+        /// can still be resolved against the auditor. Afterwards the funds can be unreserved with `withdraw_unbonded`.
         ///
         pub fn cancel_account(origin: OriginFor<T>) -> DispatchResult {
             let sender = ensure_signed(origin)?;
@@ -237,16 +987,72 @@ pub mod pallet {
                 <AuditorMap<T>>::contains_key(&sender),
                 Error::<T>::UnknownAuditor
             );
+            ensure!(
+                !<Unbonding<T>>::contains_key(&sender),
+                Error::<T>::AuditorLeaving
+            );
+            ensure!(
+                <ClaimedValue<T>>::get(&sender).is_zero(),
+                Error::<T>::ClaimedReviews
+            );
 
-            T::Currency::unreserve(&sender, T::MinAuditorStake::get());
+            let unlock_at = <frame_system::Pallet<T>>::block_number() + T::UnbondingPeriod::get();
+            <Unbonding<T>>::insert(&sender, unlock_at);
+            <ApplicationDeadline<T>>::remove(&sender);
 
-            <AuditorMap<T>>::remove(sender);
+            Self::deposit_event(Event::AccountCancelled {
+                who: sender,
+                unlock_at,
+            });
 
             Ok(())
         }
 
-        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
+        #[pallet::weight(
+            Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(4)
+                + Pallet::<T>::max_remove_auditor_weight()
+        )]
+        /// Is called by an auditor which cancelled their account to unreserve the associated funds after the
+        /// `UnbondingPeriod` is over. This finally removes the auditor account.
+        ///
+        pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            let unlock_at = <Unbonding<T>>::get(&sender).ok_or(Error::<T>::NotUnbonding)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= unlock_at,
+                Error::<T>::UnbondingPeriodNotOver
+            );
+
+            ensure!(
+                <OpenChallenges<T>>::get(&sender) == 0,
+                Error::<T>::OpenChallenge
+            );
+            ensure!(
+                !<AuditorTeam<T>>::contains_key(&sender),
+                Error::<T>::StillInTeam
+            );
+            ensure!(
+                <ClaimedValue<T>>::get(&sender).is_zero(),
+                Error::<T>::ClaimedReviews
+            );
+
+            let weight = Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(4)
+                + Self::remove_auditor(&sender);
+
+            Self::deposit_event(Event::Withdrawn { who: sender });
+
+            Ok(Some(weight).into())
+        }
+
+        #[pallet::weight(
+            Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads_writes(T::RequiredApprovals::get() as u64 + 2, 4)
+        )]
         /// Is called to give an approval to someone who signed up as an auditor. Can only be called by user's which are already approved auditors.
+        /// If `RequireIdentity` is set, the user needs an identity which was verified by a registrar.
         /// # Arguments
         ///
         /// * `to_approve` - the account id of type `T::AccountId` of the user which should get approved.
@@ -259,16 +1065,35 @@ pub mod pallet {
                 <AuditorMap<T>>::try_get(&sender).map_err(|_| Error::<T>::UnknownAuditor)?;
             let sender_score = sender_data.score.ok_or(Error::<T>::UnapprovedAuditor)?;
             ensure!(
-                sender_score >= T::MinimalApproverScore::get(),
+                sender_score >= Self::privilege_threshold(Privilege::ApproveAuditors),
                 Error::<T>::ReputationTooLow
             );
+            ensure!(!Self::is_leaving(&sender), Error::<T>::AuditorLeaving);
+            ensure!(
+                Self::is_sufficiently_staked(&sender),
+                Error::<T>::InsufficientStake
+            );
 
             // Get data of user which should get approved
             let mut to_approve_data =
                 <AuditorMap<T>>::try_get(&to_approve).map_err(|_| Error::<T>::UnknownApprovee)?;
 
-            // Make sure that has not already auditor status
+            // Make sure that has not already auditor status and is not leaving
             ensure!(to_approve_data.score.is_none(), Error::<T>::AlreadyAuditor);
+            ensure!(!Self::is_leaving(&to_approve), Error::<T>::AuditorLeaving);
+
+            // Make sure that the identity of the user was verified, if required
+            ensure!(
+                !T::RequireIdentity::get()
+                    || T::IdentityVerifier::identity_status(&to_approve)
+                        == IdentityStatus::Verified,
+                Error::<T>::IdentityNotVerified
+            );
+
+            // Approvals of accounts which are no approved auditors anymore don't count
+            to_approve_data.approved_by.retain(|approver| {
+                <AuditorMap<T>>::get(approver).map_or(false, |data| data.score.is_some())
+            });
 
             // Make sure that user was not already approved by sender
             ensure!(
@@ -282,54 +1107,1240 @@ pub mod pallet {
                 .try_push(sender)
                 .map_err(|_| Error::<T>::StorageOverflow)?;
 
-            // If user has 3 approvals, give user Auditor status
-            if to_approve_data.approved_by.len() == 3 {
+            // If user has enough approvals, give user Auditor status
+            let approved = Self::has_enough_approvals(&to_approve_data.approved_by);
+            if approved {
                 to_approve_data.score = Some(T::InitialAuditorScore::get());
+                <ApplicationDeadline<T>>::remove(&to_approve);
             }
 
             // Update user data
-            <AuditorMap<T>>::insert(to_approve, to_approve_data);
+            <AuditorMap<T>>::insert(&to_approve, to_approve_data);
+
+            // The privileges unlocked by the initial score require their stake. As for score increases by games, an
+            // auditor who cannot afford it is approved anyway and only receives the privileges after topping up.
+            if approved {
+                let _ = Self::reserve_required_stake(&to_approve);
+            }
 
             Ok(())
         }
 
         #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
-        /// Returns a person with the name given them
-        ///
+        /// Is called by an auditor to revoke an approval they gave to a pending auditor, e.g. if it was given by mistake.
         /// # Arguments
         ///
-        /// * `player0` - AccountId of player 0
-        ///
-        /// * `player1` - AccountId of player 1
+        /// * `approvee` - the account id of type `T::AccountId` of the pending auditor.
         ///
+        pub fn revoke_approval(origin: OriginFor<T>, approvee: T::AccountId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-        pub fn game_result(
-            origin: OriginFor<T>,
-            player0: T::AccountId,
-            player1: T::AccountId,
-            winner: Winner,
-        ) -> DispatchResult {
-            ensure_root(origin)?;
+            let mut approvee_data =
+                <AuditorMap<T>>::try_get(&approvee).map_err(|_| Error::<T>::UnknownApprovee)?;
+
+            // Approvals of approved auditors are final
+            ensure!(approvee_data.score.is_none(), Error::<T>::AlreadyAuditor);
+
+            let position = approvee_data
+                .approved_by
+                .iter()
+                .position(|approver| approver == &sender)
+                .ok_or(Error::<T>::NotApproved)?;
+            approvee_data.approved_by.remove(position);
+
+            <AuditorMap<T>>::insert(&approvee, approvee_data);
 
-            <Self as Game<_>>::apply_result(player0, player1, winner)?;
+            Self::deposit_event(Event::ApprovalRevoked {
+                approver: sender,
+                approvee,
+            });
 
             Ok(())
         }
-    }
 
-    pub trait Game<T: frame_system::Config> {
-        /// Is called after a auditor was challenged to transmit the result of the challenge
-        ///
-        /// * `player0` - ``T::AccountId`` of player 0
+        #[pallet::weight(
+            Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(1)
+                + Pallet::<T>::max_remove_auditor_weight()
+        )]
+        /// Can be called by anyone to remove a pending application which did not receive the auditor status within
+        /// `ApplicationExpiry` blocks. The stake is returned to the applicant.
+        /// # Arguments
         ///
-        /// * `player1` - ``T::AccountId`` of player 1
+        /// * `applicant` - the account id of type `T::AccountId` of the pending auditor.
+        ///
+        pub fn expire_application(
+            origin: OriginFor<T>,
+            applicant: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+
+            let deadline = <ApplicationDeadline<T>>::get(&applicant)
+                .ok_or(Error::<T>::NoPendingApplication)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= deadline,
+                Error::<T>::ApplicationNotExpired
+            );
+
+            let weight = Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(1)
+                + Self::remove_auditor(&applicant);
+
+            Self::deposit_event(Event::ApplicationExpired { who: applicant });
+
+            Ok(Some(weight).into())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(4, 4))]
+        /// Is called by an approved auditor to challenge a finding of another approved auditor. The challenger bonds
+        /// `ChallengeBond` and the defendant has `ResponsePeriod` blocks to accept the challenge.
+        /// # Arguments
+        ///
+        /// * `defendant` - the account id of type `T::AccountId` of the challenged auditor
+        ///
+        /// * `context` - the disputed review, the severity of the missed finding and the domain of the challenge
+        ///
+        pub fn open_challenge(
+            origin: OriginFor<T>,
+            defendant: T::AccountId,
+            context: ChallengeContext<T::Hash>,
+        ) -> DispatchResult {
+            let challenger = ensure_signed(origin)?;
+
+            <Self as Game<_>>::challenge(challenger, defendant, context)
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(2, 2))]
+        /// Is called by the defendant to accept a challenge by bonding `ChallengeBond`. Afterwards the jury can vote
+        /// for `VotingPeriod` blocks.
+        /// # Arguments
+        ///
+        /// * `id` - the id of the challenge
+        ///
+        pub fn accept_challenge(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut challenge = <Challenges<T>>::get(id).ok_or(Error::<T>::UnknownChallenge)?;
+            ensure!(challenge.defendant == sender, Error::<T>::NotDefendant);
+            ensure!(
+                challenge.phase == ChallengePhase::AwaitingResponse,
+                Error::<T>::WrongChallengePhase
+            );
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now <= challenge.deadline,
+                Error::<T>::ChallengeDeadlinePassed
+            );
+
+            T::Currency::reserve(&sender, T::ChallengeBond::get())?;
+
+            challenge.phase = ChallengePhase::Voting;
+            challenge.deadline = now + T::VotingPeriod::get();
+            <Challenges<T>>::insert(id, challenge);
+
+            Self::deposit_event(Event::ChallengeAccepted { id });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(3, 1))]
+        /// Is called by an auditor with the `JuryDuty` privilege to vote on an accepted challenge
+        /// # Arguments
+        ///
+        /// * `id` - the id of the challenge
+        ///
+        /// * `winner` - the winner the juror votes for, `Winner::Player0` is the challenger and `Winner::Player1` the
+        ///              defendant. `Winner::Draw` is an abstention.
+        ///
+        pub fn vote(origin: OriginFor<T>, id: u32, winner: Winner) -> DispatchResult {
+            let juror = ensure_signed(origin)?;
+
+            let mut challenge = <Challenges<T>>::get(id).ok_or(Error::<T>::UnknownChallenge)?;
+            ensure!(
+                challenge.phase == ChallengePhase::Voting,
+                Error::<T>::WrongChallengePhase
+            );
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() <= challenge.deadline,
+                Error::<T>::ChallengeDeadlinePassed
+            );
+            ensure!(
+                juror != challenge.challenger && juror != challenge.defendant,
+                Error::<T>::PartyCannotVote
+            );
+            ensure!(
+                <Self as PrivilegeProvider<_>>::has_privilege(&juror, Privilege::JuryDuty),
+                Error::<T>::NotJuror
+            );
+            ensure!(
+                !challenge.votes.iter().any(|(voter, _)| *voter == juror),
+                Error::<T>::AlreadyVoted
+            );
+
+            challenge
+                .votes
+                .try_push((juror.clone(), winner.clone()))
+                .map_err(|_| Error::<T>::TooManyVotes)?;
+            <Challenges<T>>::insert(id, challenge);
+
+            Self::deposit_event(Event::JuryVoted { id, juror, winner });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(12, 12))]
+        /// Can be called by anyone to resolve a challenge after its deadline. If the defendant did not accept the
+        /// challenge, the challenger wins. Otherwise the majority of the jury votes decides, a tie is a draw.
+        /// # Arguments
+        ///
+        /// * `id` - the id of the challenge
+        ///
+        pub fn resolve_challenge(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let challenge = <Challenges<T>>::get(id).ok_or(Error::<T>::UnknownChallenge)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() > challenge.deadline,
+                Error::<T>::ChallengeDeadlineNotPassed
+            );
+
+            let winner = match challenge.phase {
+                ChallengePhase::AwaitingResponse => Winner::Player0,
+                ChallengePhase::Voting => Self::tally(&challenge.votes),
+            };
+
+            Self::settle_challenge(id, challenge, winner)
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(12, 12))]
+        /// Is called by `OracleOrigin` to resolve an open challenge directly, regardless of its phase and deadline
+        /// # Arguments
+        ///
+        /// * `id` - the id of the challenge
+        ///
+        /// * `winner` - the winner of the challenge, `Winner::Player0` is the challenger and `Winner::Player1` the
+        ///              defendant
+        ///
+        pub fn oracle_resolve(origin: OriginFor<T>, id: u32, winner: Winner) -> DispatchResult {
+            T::OracleOrigin::ensure_origin(origin)?;
+
+            let challenge = <Challenges<T>>::get(id).ok_or(Error::<T>::UnknownChallenge)?;
+
+            Self::settle_challenge(id, challenge, winner)
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(7, 7))]
+        /// Submits the result of a challenge directly. Can only be called by root to override the signed challenge
+        /// protocol.
+        ///
+        /// # Arguments
+        ///
+        /// * `player0` - AccountId of player 0
+        ///
+        /// * `player1` - AccountId of player 1
+        ///
+        /// * `context` - the review, severity and domain of the challenge
+        ///
+
+        pub fn game_result(
+            origin: OriginFor<T>,
+            player0: T::AccountId,
+            player1: T::AccountId,
+            winner: Winner,
+            context: ChallengeContext<T::Hash>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            <Self as Game<_>>::apply_result(player0, player1, winner, context)?;
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(3, 2))]
+        /// Is called by an auditor to reserve additional stake, e.g. to unlock the stake required for privileges or
+        /// for claiming reviews of higher value
+        /// # Arguments
+        ///
+        /// * `amount` - the amount which is added to the stake
+        ///
+        pub fn top_up_stake(origin: OriginFor<T>, amount: DepositBalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                <AuditorMap<T>>::contains_key(&sender),
+                Error::<T>::UnknownAuditor
+            );
+            ensure!(!Self::is_leaving(&sender), Error::<T>::AuditorLeaving);
+
+            T::Currency::reserve(&sender, amount)?;
+            let stake = <AuditorStake<T>>::mutate(&sender, |stake| {
+                *stake = stake.saturating_add(amount);
+                *stake
+            });
+
+            Self::deposit_event(Event::StakeChanged { who: sender, stake });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(4, 2))]
+        /// Is called by an auditor to unreserve stake which is not required for their privileges and claimed reviews
+        /// # Arguments
+        ///
+        /// * `amount` - the amount which is removed from the stake
+        ///
+        pub fn reduce_stake(origin: OriginFor<T>, amount: DepositBalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                <AuditorMap<T>>::contains_key(&sender),
+                Error::<T>::UnknownAuditor
+            );
+
+            let stake = <AuditorStake<T>>::get(&sender)
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientStake)?;
+            ensure!(
+                stake >= Self::required_stake(&sender, <ClaimedValue<T>>::get(&sender)),
+                Error::<T>::InsufficientStake
+            );
+
+            T::Currency::unreserve(&sender, amount);
+            <AuditorStake<T>>::insert(&sender, stake);
+
+            Self::deposit_event(Event::StakeChanged { who: sender, stake });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(3, 2))]
+        /// Is called by any account to bond funds behind an approved auditor. Endorsers share in the auditor's review
+        /// fees and in the slashes if the auditor loses a challenge. Endorsing the same auditor again adds to the
+        /// existing endorsement.
+        /// # Arguments
+        ///
+        /// * `auditor` - the account id of type `T::AccountId` of the endorsed auditor
+        ///
+        /// * `amount` - the amount which is bonded behind the auditor
+        ///
+        pub fn endorse(
+            origin: OriginFor<T>,
+            auditor: T::AccountId,
+            amount: DepositBalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(sender != auditor, Error::<T>::SelfEndorsement);
+            let auditor_data =
+                <AuditorMap<T>>::try_get(&auditor).map_err(|_| Error::<T>::UnknownAuditor)?;
+            ensure!(auditor_data.score.is_some(), Error::<T>::UnapprovedAuditor);
+            ensure!(!Self::is_leaving(&auditor), Error::<T>::AuditorLeaving);
+
+            let total = <Endorsements<T>>::try_mutate(&auditor, |endorsements| {
+                let total = match endorsements
+                    .iter_mut()
+                    .find(|(endorser, _)| *endorser == sender)
+                {
+                    Some((_, endorsed)) => {
+                        *endorsed = endorsed.saturating_add(amount);
+                        *endorsed
+                    }
+                    None => {
+                        endorsements
+                            .try_push((sender.clone(), amount))
+                            .map_err(|_| Error::<T>::TooManyEndorsers)?;
+                        amount
+                    }
+                };
+                ensure!(
+                    total >= T::MinEndorsement::get(),
+                    Error::<T>::EndorsementTooLow
+                );
+                Ok::<_, DispatchError>(total)
+            })?;
+            T::Currency::reserve(&sender, amount)?;
+
+            Self::deposit_event(Event::Endorsed {
+                endorser: sender,
+                auditor,
+                amount: total,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(2, 1))]
+        /// Is called by an endorser to unreserve their whole endorsement of an auditor. Endorsements cannot be
+        /// withdrawn while the auditor is party of an open challenge.
+        /// # Arguments
+        ///
+        /// * `auditor` - the account id of type `T::AccountId` of the endorsed auditor
+        ///
+        pub fn withdraw_endorsement(origin: OriginFor<T>, auditor: T::AccountId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                <OpenChallenges<T>>::get(&auditor) == 0,
+                Error::<T>::OpenChallenge
+            );
+
+            let amount = <Endorsements<T>>::try_mutate_exists(&auditor, |endorsements| {
+                let list = endorsements.as_mut().ok_or(Error::<T>::NotEndorsed)?;
+                let index = list
+                    .iter()
+                    .position(|(endorser, _)| *endorser == sender)
+                    .ok_or(Error::<T>::NotEndorsed)?;
+                let (_, amount) = list.remove(index);
+                if list.is_empty() {
+                    *endorsements = None;
+                }
+                Ok::<_, DispatchError>(amount)
+            })?;
+            T::Currency::unreserve(&sender, amount);
+
+            Self::deposit_event(Event::EndorsementWithdrawn {
+                endorser: sender,
+                auditor,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(3, 3))]
+        /// Is called by an approved auditor to create a team. The caller becomes the admin and first member of the team.
+        /// # Arguments
+        ///
+        /// * `shares` - the caller's shares of the review fees paid to the team
+        ///
+        pub fn create_team(origin: OriginFor<T>, shares: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_can_join_team(&sender, shares)?;
+
+            let team = <NextTeamId<T>>::get();
+            <NextTeamId<T>>::put(team.checked_add(1).ok_or(Error::<T>::StorageOverflow)?);
+
+            let mut members = BoundedVec::default();
+            members
+                .try_push((sender.clone(), shares))
+                .map_err(|_| Error::<T>::TooManyTeamMembers)?;
+            <Teams<T>>::insert(
+                team,
+                Team {
+                    admin: sender.clone(),
+                    members,
+                },
+            );
+            <AuditorTeam<T>>::insert(&sender, team);
+
+            Self::deposit_event(Event::TeamCreated {
+                team,
+                admin: sender,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(2, 1))]
+        /// Is called by the team admin to invite an auditor, the auditor joins the team by accepting the invitation
+        /// # Arguments
+        ///
+        /// * `team` - the id of the team
+        ///
+        /// * `who` - the account id of type `T::AccountId` of the invited auditor
+        ///
+        /// * `shares` - the shares of the review fees paid to the team which are offered to the auditor
+        ///
+        pub fn invite_to_team(
+            origin: OriginFor<T>,
+            team: TeamId,
+            who: T::AccountId,
+            shares: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_team_admin(team, &sender)?;
+            ensure!(shares > 0, Error::<T>::ZeroShares);
+
+            <TeamInvites<T>>::insert(&who, team, shares);
+
+            Self::deposit_event(Event::TeamInviteSent { team, who, shares });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(4, 3))]
+        /// Is called by an invited auditor to join a team
+        /// # Arguments
+        ///
+        /// * `team` - the id of the team
+        ///
+        pub fn accept_team_invite(origin: OriginFor<T>, team: TeamId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let shares = <TeamInvites<T>>::get(&sender, team).ok_or(Error::<T>::NoTeamInvite)?;
+            Self::ensure_can_join_team(&sender, shares)?;
+
+            <Teams<T>>::try_mutate(team, |team_data| {
+                team_data
+                    .as_mut()
+                    .ok_or(Error::<T>::UnknownTeam)?
+                    .members
+                    .try_push((sender.clone(), shares))
+                    .map_err(|_| Error::<T>::TooManyTeamMembers)
+            })?;
+            <TeamInvites<T>>::remove(&sender, team);
+            <AuditorTeam<T>>::insert(&sender, team);
+
+            Self::deposit_event(Event::TeamJoined { team, who: sender });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(2, 2))]
+        /// Is called by a team member to leave their team. The admin can only leave as the last member, which
+        /// dissolves the team.
+        ///
+        pub fn leave_team(origin: OriginFor<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let team = <AuditorTeam<T>>::get(&sender).ok_or(Error::<T>::NotTeamMember)?;
+            let mut team_data = <Teams<T>>::get(team).ok_or(Error::<T>::UnknownTeam)?;
+            ensure!(
+                team_data.admin != sender || team_data.members.len() == 1,
+                Error::<T>::TeamAdminCannotLeave
+            );
+
+            team_data.members.retain(|(member, _)| *member != sender);
+            <AuditorTeam<T>>::remove(&sender);
+            Self::deposit_event(Event::TeamLeft { team, who: sender });
+
+            if team_data.members.is_empty() {
+                <Teams<T>>::remove(team);
+                Self::deposit_event(Event::TeamDissolved { team });
+            } else {
+                <Teams<T>>::insert(team, team_data);
+            }
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(1, 1))]
+        /// Is called by the team admin to change the shares of a member
+        /// # Arguments
+        ///
+        /// * `team` - the id of the team
+        ///
+        /// * `who` - the account id of type `T::AccountId` of the member
+        ///
+        /// * `shares` - the new shares of the member
+        ///
+        pub fn set_team_shares(
+            origin: OriginFor<T>,
+            team: TeamId,
+            who: T::AccountId,
+            shares: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let mut team_data = Self::ensure_team_admin(team, &sender)?;
+            ensure!(shares > 0, Error::<T>::ZeroShares);
+            let member_shares = team_data
+                .members
+                .iter_mut()
+                .find(|(member, _)| *member == who)
+                .map(|(_, member_shares)| member_shares)
+                .ok_or(Error::<T>::NotTeamMember)?;
+            *member_shares = shares;
+            <Teams<T>>::insert(team, team_data);
+
+            Self::deposit_event(Event::TeamSharesSet { team, who, shares });
+
+            Ok(())
+        }
+
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().writes(1))]
+        /// Sets the minimal score which is required for a privilege. Can only be called by root.
+        /// # Arguments
+        ///
+        /// * `privilege` - the privilege of type `Privilege`
+        ///
+        /// * `threshold` - the required score, if `None` the threshold is reset to `MinimalApproverScore`
+        ///
+        pub fn set_privilege_threshold(
+            origin: OriginFor<T>,
+            privilege: Privilege,
+            threshold: Option<u32>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            match threshold {
+                Some(threshold) => <PrivilegeThresholds<T>>::insert(privilege, threshold),
+                None => <PrivilegeThresholds<T>>::remove(privilege),
+            }
+
+            Self::deposit_event(Event::PrivilegeThresholdSet {
+                privilege,
+                threshold,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::weight(
+            Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(1)
+                + Pallet::<T>::max_clear_entries_weight()
+        )]
+        /// Can be called by anyone to continue removing the pair cooldowns and team invitations of a removed auditor,
+        /// if there were more than `MaxAuditorCleanup` entries
+        /// # Arguments
+        ///
+        /// * `who` - the account id of type `T::AccountId` of the removed auditor.
+        ///
+        pub fn clear_auditor_data(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+
+            ensure!(
+                <PendingCleanup<T>>::contains_key(&who),
+                Error::<T>::NothingToClean
+            );
+
+            let weight = Weight::from_ref_time(10_000)
+                + T::DbWeight::get().reads(1)
+                + Self::clear_entries(&who);
+
+            Ok(Some(weight).into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Checks the length of a profile locator and converts it into its bounded representation
+        fn bound_profile_locator(
+            profile_locator: Option<Vec<u8>>,
+        ) -> Result<Option<BoundedVec<u8, T::MaxProfileLocatorLength>>, Error<T>> {
+            profile_locator
+                .map(|locator| {
+                    ensure!(!locator.is_empty(), Error::<T>::EmptyProfileLocator);
+                    locator
+                        .try_into()
+                        .map_err(|_| Error::<T>::ProfileLocatorTooLong)
+                })
+                .transpose()
+        }
+
+        /// Unreserves the stake of `who` and removes the auditor together with all data kept per auditor. Endorsements
+        /// are kept, so that the endorsers can still withdraw them. Returns the weight of the removal.
+        fn remove_auditor(who: &T::AccountId) -> Weight {
+            T::Currency::unreserve(who, <AuditorStake<T>>::take(who));
+
+            <AuditorMap<T>>::remove(who);
+            <Unbonding<T>>::remove(who);
+            <ApplicationDeadline<T>>::remove(who);
+            for domain in Domain::ALL {
+                <DomainScores<T>>::remove(who, domain);
+            }
+            <LastGame<T>>::remove(who);
+            <GamesInPeriod<T>>::remove(who);
+            <ClaimedValue<T>>::remove(who);
+
+            Self::remove_auditor_base_weight() + Self::clear_entries(who)
+        }
+
+        /// Removes up to `MaxAuditorCleanup` pair cooldowns and team invitations of a removed auditor. The auditor is
+        /// kept in `PendingCleanup` as long as entries are left. Returns the weight of the removal.
+        fn clear_entries(who: &T::AccountId) -> Weight {
+            let limit = T::MaxAuditorCleanup::get() as usize;
+
+            let mut cleared = 0;
+            for (opponent, _) in <LastPairGame<T>>::drain_prefix(who).take(limit) {
+                <LastPairGame<T>>::remove(opponent, who);
+                cleared += 1;
+            }
+            let pair_games = cleared;
+            cleared += <TeamInvites<T>>::drain_prefix(who)
+                .take(limit - cleared)
+                .count();
+
+            if <LastPairGame<T>>::iter_prefix(who).next().is_none()
+                && <TeamInvites<T>>::iter_prefix(who).next().is_none()
+            {
+                <PendingCleanup<T>>::remove(who);
+            } else {
+                <PendingCleanup<T>>::insert(who, ());
+            }
+
+            T::DbWeight::get().reads_writes(cleared as u64 + 2, (pair_games + cleared) as u64 + 1)
+        }
+
+        /// Weight of `remove_auditor` without the removal of pair cooldowns and team invitations
+        fn remove_auditor_base_weight() -> Weight {
+            T::DbWeight::get().reads_writes(2, 8 + Domain::ALL.len() as u64)
+        }
+
+        /// Maximum weight of `clear_entries`
+        pub(crate) fn max_clear_entries_weight() -> Weight {
+            let limit = T::MaxAuditorCleanup::get() as u64;
+            T::DbWeight::get().reads_writes(limit + 2, 2 * limit + 1)
+        }
+
+        /// Maximum weight of `remove_auditor`
+        pub(crate) fn max_remove_auditor_weight() -> Weight {
+            Self::remove_auditor_base_weight() + Self::max_clear_entries_weight()
+        }
+
+        /// Returns the index of the rating period `now` belongs to, or `None` if the number of games is not limited
+        fn rating_period(now: T::BlockNumber) -> Option<T::BlockNumber> {
+            let period = T::RatingPeriod::get();
+            (!period.is_zero()).then(|| now / period)
+        }
+
+        /// Checks that neither the pair nor one of the auditors is in cooldown and that both auditors can play
+        /// another rated game in the current rating period
+        fn ensure_can_play(player0: &T::AccountId, player1: &T::AccountId) -> DispatchResult {
+            let now = <frame_system::Pallet<T>>::block_number();
+            let in_cooldown = |last: Option<T::BlockNumber>, cooldown: T::BlockNumber| {
+                last.map_or(false, |last| now < last + cooldown)
+            };
+
+            ensure!(
+                !in_cooldown(
+                    <LastPairGame<T>>::get(player0, player1),
+                    T::PairCooldown::get()
+                ),
+                Error::<T>::PairCooldown
+            );
+
+            let current_period = Self::rating_period(now);
+            for player in [player0, player1] {
+                ensure!(
+                    !in_cooldown(<LastGame<T>>::get(player), T::AuditorCooldown::get()),
+                    Error::<T>::AuditorCooldown
+                );
+                if let (Some(current_period), Some((period, games))) =
+                    (current_period, <GamesInPeriod<T>>::get(player))
+                {
+                    ensure!(
+                        period != current_period || games < T::MaxGamesPerPeriod::get(),
+                        Error::<T>::TooManyGames
+                    );
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Records a rated game for the cooldowns and the number of games per rating period
+        fn record_game(player0: &T::AccountId, player1: &T::AccountId) {
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            <LastPairGame<T>>::insert(player0, player1, now);
+            <LastPairGame<T>>::insert(player1, player0, now);
+
+            let current_period = Self::rating_period(now);
+            for player in [player0, player1] {
+                <LastGame<T>>::insert(player, now);
+                if let Some(current_period) = current_period {
+                    <GamesInPeriod<T>>::mutate(player, |entry| {
+                        *entry = match *entry {
+                            Some((period, games)) if period == current_period => {
+                                Some((period, games.saturating_add(1)))
+                            }
+                            _ => Some((current_period, 1)),
+                        }
+                    });
+                }
+            }
+        }
+
+        /// Returns the winner which the majority of jurors voted for, or `Winner::Draw` on a tie
+        fn tally(votes: &[(T::AccountId, Winner)]) -> Winner {
+            let count = |winner: Winner| votes.iter().filter(|(_, vote)| *vote == winner).count();
+
+            match count(Winner::Player0).cmp(&count(Winner::Player1)) {
+                sp_std::cmp::Ordering::Greater => Winner::Player0,
+                sp_std::cmp::Ordering::Less => Winner::Player1,
+                sp_std::cmp::Ordering::Equal => Winner::Draw,
+            }
+        }
+
+        /// Settles the bonds of a challenge, applies the result to the scores and removes the challenge.
+        /// The loser's bond is transferred to the winner, all other bonds are returned. The scores are only updated if
+        /// neither auditor is rate limited, so that resolving a challenge cannot fail because of the rate limits. Other
+        /// errors of the rating fail the settlement, which leaves the bonds and the challenge untouched.
+        fn settle_challenge(id: u32, challenge: ChallengeOf<T>, winner: Winner) -> DispatchResult {
+            let bond = T::ChallengeBond::get();
+            let defendant_bonded = challenge.phase == ChallengePhase::Voting;
+
+            match winner {
+                Winner::Player0 if defendant_bonded => {
+                    T::Currency::repatriate_reserved(
+                        &challenge.defendant,
+                        &challenge.challenger,
+                        bond,
+                        BalanceStatus::Free,
+                    )?;
+                    T::Currency::unreserve(&challenge.challenger, bond);
+                }
+                Winner::Player1 => {
+                    T::Currency::repatriate_reserved(
+                        &challenge.challenger,
+                        &challenge.defendant,
+                        bond,
+                        BalanceStatus::Free,
+                    )?;
+                    if defendant_bonded {
+                        T::Currency::unreserve(&challenge.defendant, bond);
+                    }
+                }
+                _ => {
+                    T::Currency::unreserve(&challenge.challenger, bond);
+                    if defendant_bonded {
+                        T::Currency::unreserve(&challenge.defendant, bond);
+                    }
+                }
+            }
+
+            match winner {
+                Winner::Player0 => {
+                    Self::slash_endorsements(&challenge.defendant, &challenge.challenger)
+                }
+                Winner::Player1 => {
+                    Self::slash_endorsements(&challenge.challenger, &challenge.defendant)
+                }
+                Winner::Draw => {}
+            }
+
+            for party in [&challenge.challenger, &challenge.defendant] {
+                <OpenChallenges<T>>::mutate_exists(party, |count| {
+                    *count = count
+                        .and_then(|count| count.checked_sub(1))
+                        .filter(|count| *count > 0)
+                });
+            }
+            <Challenges<T>>::remove(id);
+
+            // Rate limited auditors keep their scores, any other error of the rating fails the whole settlement
+            let rated = winner != Winner::Draw
+                && Self::ensure_can_play(&challenge.challenger, &challenge.defendant).is_ok();
+            if rated {
+                <Self as Game<_>>::apply_result(
+                    challenge.challenger,
+                    challenge.defendant,
+                    winner.clone(),
+                    challenge.context,
+                )?;
+            }
+
+            Self::deposit_event(Event::ChallengeResolved { id, winner, rated });
+
+            Ok(())
+        }
+
+        /// Moves `EndorsementSlash` of every endorsement of `loser` to the free balance of `winner`
+        fn slash_endorsements(loser: &T::AccountId, winner: &T::AccountId) {
+            let slash_ratio = T::EndorsementSlash::get();
+            let mut slashed: DepositBalanceOf<T> = Zero::zero();
+
+            <Endorsements<T>>::mutate_exists(loser, |endorsements| {
+                if let Some(list) = endorsements {
+                    for (endorser, amount) in list.iter_mut() {
+                        let slash = slash_ratio * *amount;
+                        // `repatriate_reserved` returns the part of the slash which could not be moved
+                        let remaining = T::Currency::repatriate_reserved(
+                            endorser,
+                            winner,
+                            slash,
+                            BalanceStatus::Free,
+                        )
+                        .unwrap_or(slash);
+                        let moved = slash.saturating_sub(remaining);
+                        *amount = amount.saturating_sub(moved);
+                        slashed = slashed.saturating_add(moved);
+                    }
+                    list.retain(|(_, amount)| !amount.is_zero());
+                }
+                if endorsements.as_ref().map_or(false, |list| list.is_empty()) {
+                    *endorsements = None;
+                }
+            });
+
+            if !slashed.is_zero() {
+                Self::deposit_event(Event::EndorsementsSlashed {
+                    auditor: loser.clone(),
+                    winner: winner.clone(),
+                    amount: slashed,
+                });
+            }
+        }
+
+        /// Returns the stake `who` has to reserve with the given claimed value. It consists of `MinAuditorStake`,
+        /// `StakePerPrivilege` for every privilege the score unlocks and `ClaimedValueRatio` of the claimed value.
+        pub fn required_stake(
+            who: &T::AccountId,
+            claimed_value: DepositBalanceOf<T>,
+        ) -> DepositBalanceOf<T> {
+            let score = <AuditorMap<T>>::get(who).and_then(|data| data.score);
+            let unlocked = Privilege::ALL
+                .iter()
+                .filter(|privilege| {
+                    score.map_or(false, |score| {
+                        score >= Self::privilege_threshold(**privilege)
+                    })
+                })
+                .count() as u32;
+
+            T::MinAuditorStake::get()
+                .saturating_add(T::StakePerPrivilege::get().saturating_mul(unlocked.into()))
+                .saturating_add(T::ClaimedValueRatio::get() * claimed_value)
+        }
+
+        /// Reserves the part of the required stake of `who` which is not covered by their stake yet
+        pub(crate) fn reserve_required_stake(who: &T::AccountId) -> DispatchResult {
+            let stake = <AuditorStake<T>>::get(who);
+            let required_stake = Self::required_stake(who, <ClaimedValue<T>>::get(who));
+            if required_stake > stake {
+                T::Currency::reserve(who, required_stake - stake)?;
+                <AuditorStake<T>>::insert(who, required_stake);
+                Self::deposit_event(Event::StakeChanged {
+                    who: who.clone(),
+                    stake: required_stake,
+                });
+            }
+            Ok(())
+        }
+
+        /// Returns `true` if the stake of `who` covers the stake required for their privileges and claimed reviews
+        pub fn is_sufficiently_staked(who: &T::AccountId) -> bool {
+            <AuditorStake<T>>::get(who) >= Self::required_stake(who, <ClaimedValue<T>>::get(who))
+        }
+
+        /// Returns the reputation of a team, which is the average score of its approved members
+        pub fn team_score(team: TeamId) -> Option<u32> {
+            let team_data = <Teams<T>>::get(team)?;
+            let scores: Vec<u64> = team_data
+                .members
+                .iter()
+                .filter_map(|(member, _)| <AuditorMap<T>>::get(member).and_then(|data| data.score))
+                .map(u64::from)
+                .collect();
+            if scores.is_empty() {
+                return None;
+            }
+            Some((scores.iter().sum::<u64>() / scores.len() as u64) as u32)
+        }
+
+        /// Ensures that `who` is an approved auditor without a team, which can join a team with the given shares
+        fn ensure_can_join_team(who: &T::AccountId, shares: u32) -> DispatchResult {
+            let auditor_data =
+                <AuditorMap<T>>::try_get(who).map_err(|_| Error::<T>::UnknownAuditor)?;
+            ensure!(auditor_data.score.is_some(), Error::<T>::UnapprovedAuditor);
+            ensure!(!Self::is_leaving(who), Error::<T>::AuditorLeaving);
+            ensure!(
+                !<AuditorTeam<T>>::contains_key(who),
+                Error::<T>::AlreadyInTeam
+            );
+            ensure!(shares > 0, Error::<T>::ZeroShares);
+            Ok(())
+        }
+
+        /// Returns the team if `who` is its admin
+        fn ensure_team_admin(team: TeamId, who: &T::AccountId) -> Result<TeamOf<T>, DispatchError> {
+            let team_data = <Teams<T>>::get(team).ok_or(Error::<T>::UnknownTeam)?;
+            ensure!(team_data.admin == *who, Error::<T>::NotTeamAdmin);
+            Ok(team_data)
+        }
+
+        /// Returns the minimal score which is required for `privilege`
+        pub fn privilege_threshold(privilege: Privilege) -> u32 {
+            <PrivilegeThresholds<T>>::get(privilege).unwrap_or_else(T::MinimalApproverScore::get)
+        }
+
+        /// Returns `true` if the auditor cancelled their account and is waiting for the unbonding period to end
+        pub fn is_leaving(who: &T::AccountId) -> bool {
+            <Unbonding<T>>::contains_key(who)
+        }
+
+        /// Returns `true` if the given approvals suffice to grant the auditor status. `RequiredApprovals` approvals are
+        /// always sufficient. With score weighting, fewer approvals suffice as soon as the current scores of the approvers
+        /// add up to `RequiredApprovals * MinimalApproverScore`.
+        pub fn has_enough_approvals(approved_by: &[T::AccountId]) -> bool {
+            let required = T::RequiredApprovals::get();
+            if approved_by.len() as u32 >= required {
+                return true;
+            }
+            if !T::ScoreWeightedApprovals::get() {
+                return false;
+            }
+
+            let approvals_weight = approved_by
+                .iter()
+                .filter_map(|approver| <AuditorMap<T>>::get(approver).and_then(|data| data.score))
+                .fold(0u64, |sum, score| sum.saturating_add(score as u64));
+            approvals_weight >= required as u64 * T::MinimalApproverScore::get() as u64
+        }
+
+        /// Returns the score of an approved auditor in the given domain. If the auditor did not play a game in the domain
+        /// yet, the auditor's global score is returned.
+        pub fn domain_score(who: &T::AccountId, domain: Domain) -> Option<u32> {
+            <DomainScores<T>>::get(who, domain)
+                .or_else(|| <AuditorMap<T>>::get(who).and_then(|data| data.score))
+        }
+
+        /// Returns auditors sorted by score in descending order, auditors with equal scores are sorted by account id.
+        /// Auditors which cancelled their account are not listed. This is intended to be called off-chain through the
+        /// runtime API, since all auditors are iterated.
+        /// # Arguments
+        ///
+        /// * `status` - only auditors with this approval status are returned
+        ///
+        /// * `specialization` - if set, only auditors specialized in this domain are returned and ranked by their domain score
+        ///
+        /// * `offset` - number of auditors to skip
+        ///
+        /// * `limit` - maximum number of auditors to return, capped at `MAX_LEADERBOARD_PAGE_SIZE`
+        ///
+        pub fn leaderboard(
+            status: AuditorStatus,
+            specialization: Option<Domain>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<LeaderboardEntry<T::AccountId>> {
+            let mut entries: Vec<_> = <AuditorMap<T>>::iter()
+                .filter(|(who, data)| {
+                    let status_matches = match status {
+                        AuditorStatus::Any => true,
+                        AuditorStatus::Approved => data.score.is_some(),
+                        AuditorStatus::Pending => data.score.is_none(),
+                    };
+                    let specialization_matches = specialization
+                        .map_or(true, |domain| data.specializations.contains(&domain));
+                    status_matches && specialization_matches && !Self::is_leaving(who)
+                })
+                .map(|(account, data)| {
+                    let score = match (specialization, data.score) {
+                        (Some(domain), Some(score)) => {
+                            Some(<DomainScores<T>>::get(&account, domain).unwrap_or(score))
+                        }
+                        (_, score) => score,
+                    };
+                    LeaderboardEntry {
+                        score,
+                        profile_hash: data.profile_hash,
+                        specializations: data.specializations.into_inner(),
+                        identity: T::IdentityVerifier::identity_status(&account),
+                        account,
+                    }
+                })
+                .collect();
+
+            // `None` is less than any score, so pending auditors are listed last
+            entries.sort_by(|a, b| {
+                b.score
+                    .cmp(&a.score)
+                    .then_with(|| a.account.cmp(&b.account))
+            });
+
+            entries
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_LEADERBOARD_PAGE_SIZE) as usize)
+                .collect()
+        }
+    }
+
+    pub trait PrivilegeProvider<AccountId> {
+        /// Returns `true` if `who` is an approved auditor whose score reaches the threshold of `privilege` and whose
+        /// stake covers the required stake. Auditors which are leaving have no privileges.
+        fn has_privilege(who: &AccountId, privilege: Privilege) -> bool;
+    }
+
+    impl<T: Config> PrivilegeProvider<T::AccountId> for Pallet<T> {
+        fn has_privilege(who: &T::AccountId, privilege: Privilege) -> bool {
+            !Self::is_leaving(who)
+                && <AuditorMap<T>>::get(who)
+                    .and_then(|data| data.score)
+                    .map_or(false, |score| score >= Self::privilege_threshold(privilege))
+                && Self::is_sufficiently_staked(who)
+        }
+    }
+
+    pub trait StakeLedger<AccountId, Balance> {
+        /// Adds the deposit of a claimed review to the value claimed by `who`. Fails if the stake of `who` does not
+        /// cover the required stake afterwards.
+        fn add_claimed_value(who: &AccountId, value: Balance) -> DispatchResult;
+
+        /// Removes the deposit of a review, which is not claimed anymore, from the value claimed by `who`
+        fn release_claimed_value(who: &AccountId, value: Balance);
+    }
+
+    impl<T: Config> StakeLedger<T::AccountId, DepositBalanceOf<T>> for Pallet<T> {
+        fn add_claimed_value(who: &T::AccountId, value: DepositBalanceOf<T>) -> DispatchResult {
+            let claimed_value = <ClaimedValue<T>>::get(who).saturating_add(value);
+            ensure!(
+                <AuditorStake<T>>::get(who) >= Self::required_stake(who, claimed_value),
+                Error::<T>::InsufficientStake
+            );
+            <ClaimedValue<T>>::insert(who, claimed_value);
+            Ok(())
+        }
+
+        fn release_claimed_value(who: &T::AccountId, value: DepositBalanceOf<T>) {
+            <ClaimedValue<T>>::mutate_exists(who, |claimed_value| {
+                *claimed_value = claimed_value
+                    .map(|claimed_value| claimed_value.saturating_sub(value))
+                    .filter(|claimed_value| !claimed_value.is_zero())
+            });
+        }
+    }
+
+    pub trait FeeDistributor<AccountId, Balance> {
+        /// Transfers a review fee from the free balance of `payer` to `auditor` and the auditor's endorsers
+        fn distribute_fee(payer: &AccountId, auditor: &AccountId, fee: Balance) -> DispatchResult;
+    }
+
+    /// `EndorserFeeShare` of the fee is split among the endorsers in proportion to their endorsements, the rest is
+    /// paid to the auditor
+    impl<T: Config> FeeDistributor<T::AccountId, DepositBalanceOf<T>> for Pallet<T> {
+        fn distribute_fee(
+            payer: &T::AccountId,
+            auditor: &T::AccountId,
+            fee: DepositBalanceOf<T>,
+        ) -> DispatchResult {
+            let endorsements = <Endorsements<T>>::get(auditor);
+            let total = endorsements
+                .iter()
+                .fold(Zero::zero(), |sum: DepositBalanceOf<T>, (_, amount)| {
+                    sum.saturating_add(*amount)
+                });
+            let endorser_pool = if total.is_zero() {
+                Zero::zero()
+            } else {
+                T::EndorserFeeShare::get() * fee
+            };
+
+            let mut endorser_share: DepositBalanceOf<T> = Zero::zero();
+            for (endorser, amount) in endorsements.iter() {
+                let share = Perbill::from_rational(*amount, total) * endorser_pool;
+                T::Currency::transfer(payer, endorser, share, ExistenceRequirement::AllowDeath)?;
+                endorser_share = endorser_share.saturating_add(share);
+            }
+            T::Currency::transfer(
+                payer,
+                auditor,
+                fee.saturating_sub(endorser_share),
+                ExistenceRequirement::AllowDeath,
+            )?;
+
+            Self::deposit_event(Event::FeeDistributed {
+                auditor: auditor.clone(),
+                fee,
+                endorser_share,
+            });
+
+            Ok(())
+        }
+    }
+
+    pub trait TeamProvider<AccountId, Balance> {
+        /// Returns the team `who` is member of
+        fn team_of(who: &AccountId) -> Option<TeamId>;
+
+        /// Returns `true` if the reputation of the team unlocks `privilege`
+        fn team_has_privilege(team: TeamId, privilege: Privilege) -> bool;
+
+        /// Transfers a review fee from the free balance of `payer` to the members of `team` according to their shares
+        fn distribute_team_fee(payer: &AccountId, team: TeamId, fee: Balance) -> DispatchResult;
+    }
+
+    /// The share of every member is paid through `FeeDistributor`, so that the members' endorsers are paid as well.
+    /// Rounding remainders are paid to the team admin.
+    impl<T: Config> TeamProvider<T::AccountId, DepositBalanceOf<T>> for Pallet<T> {
+        fn team_of(who: &T::AccountId) -> Option<TeamId> {
+            <AuditorTeam<T>>::get(who)
+        }
+
+        fn team_has_privilege(team: TeamId, privilege: Privilege) -> bool {
+            Self::team_score(team)
+                .map_or(false, |score| score >= Self::privilege_threshold(privilege))
+        }
+
+        fn distribute_team_fee(
+            payer: &T::AccountId,
+            team: TeamId,
+            fee: DepositBalanceOf<T>,
+        ) -> DispatchResult {
+            let team_data = <Teams<T>>::get(team).ok_or(Error::<T>::UnknownTeam)?;
+            let total_shares = team_data
+                .members
+                .iter()
+                .fold(0u32, |sum, (_, shares)| sum.saturating_add(*shares));
+
+            let mut paid: DepositBalanceOf<T> = Zero::zero();
+            for (member, shares) in team_data.members.iter() {
+                if *member == team_data.admin {
+                    continue;
+                }
+                let share = Perbill::from_rational(*shares, total_shares) * fee;
+                Self::distribute_fee(payer, member, share)?;
+                paid = paid.saturating_add(share);
+            }
+            Self::distribute_fee(payer, &team_data.admin, fee.saturating_sub(paid))?;
+
+            Self::deposit_event(Event::TeamFeeDistributed { team, fee });
+
+            Ok(())
+        }
+    }
+
+    pub trait IdentityVerifier<AccountId> {
+        /// Returns the judgement of the on-chain identity of `who`
+        fn identity_status(who: &AccountId) -> IdentityStatus;
+    }
+
+    /// Used if the runtime has no identity system, no auditor has an identity
+    impl<AccountId> IdentityVerifier<AccountId> for () {
+        fn identity_status(_who: &AccountId) -> IdentityStatus {
+            IdentityStatus::NotSet
+        }
+    }
+
+    pub trait Game<T: frame_system::Config> {
+        /// Is called after a auditor was challenged to transmit the result of the challenge
+        ///
+        /// * `player0` - ``T::AccountId`` of player 0
+        ///
+        /// * `player1` - ``T::AccountId`` of player 1
         ///
         /// * `winner` - ``Winner`` the enum that indicates who won the challenge
         ///
+        /// * `context` - ``ChallengeContext`` the disputed review, the severity by which the rating change is weighted
+        ///               and the domain of the challenge
+        ///
         fn apply_result(
             player0: T::AccountId,
             player1: T::AccountId,
             winner: Winner,
+            context: ChallengeContext<T::Hash>,
+        ) -> DispatchResult;
+
+        /// Opens a challenge, which is resolved by the bonds, the response period and the jury of the challenge
+        /// protocol
+        ///
+        /// * `challenger` - ``T::AccountId`` of the approved auditor who challenges
+        ///
+        /// * `defendant` - ``T::AccountId`` of the challenged auditor
+        ///
+        /// * `context` - ``ChallengeContext`` the disputed review, the severity of the missed finding and the domain
+        ///
+        fn challenge(
+            challenger: T::AccountId,
+            defendant: T::AccountId,
+            context: ChallengeContext<T::Hash>,
         ) -> DispatchResult;
     }
 
@@ -343,19 +2354,35 @@ pub mod pallet {
         ///
         /// * `winner` - ``Winner`` the enum that indicates who won the challenge
         ///
+        /// * `context` - ``ChallengeContext`` the disputed review, the severity by which the rating change is weighted
+        ///               and the domain of the challenge
+        ///
 
         fn apply_result(
             player0: T::AccountId,
             player1: T::AccountId,
             winner: Winner,
+            context: ChallengeContext<T::Hash>,
         ) -> DispatchResult {
+            let ChallengeContext {
+                review_hash,
+                severity,
+                domain,
+            } = context;
+
             // Get data and particularly scores of both players
             let mut player0_data =
                 <AuditorMap<T>>::try_get(&player0).map_err(|_| Error::<T>::UnknownAuditor)?;
-            let player0_score = player0_data.score.ok_or(Error::<T>::UnapprovedAuditor)?;
+            let mut player0_score = player0_data.score.ok_or(Error::<T>::UnapprovedAuditor)?;
             let mut player1_data =
                 <AuditorMap<T>>::try_get(&player1).map_err(|_| Error::<T>::UnknownAuditor)?;
-            let player1_score = player1_data.score.ok_or(Error::<T>::UnapprovedAuditor)?;
+            let mut player1_score = player1_data.score.ok_or(Error::<T>::UnapprovedAuditor)?;
+
+            // Use the domain specific scores, if the challenge belongs to a domain
+            if let Some(domain) = domain {
+                player0_score = <DomainScores<T>>::get(&player0, domain).unwrap_or(player0_score);
+                player1_score = <DomainScores<T>>::get(&player1, domain).unwrap_or(player1_score);
+            }
 
             // Map winner and looser scores accordingly
             let (winner_score, looser_score) = match winner {
@@ -364,28 +2391,90 @@ pub mod pallet {
                 _ => return Ok(()),
             };
 
-            // Instantiate EloRank, compute new scores
-            let elo = EloRank { k: 32 };
+            Self::ensure_can_play(&player0, &player1)?;
+
+            // Instantiate EloRank weighted by severity, compute new scores
+            let elo = EloRank {
+                k: severity.k_factor(),
+            };
             let (winner_new, looser_new) = elo
                 .calculate(winner_score, looser_score)
                 .map_err(|_| Error::<T>::UnexpectedEloOverflow)?;
 
             // Map score results accordingly
-            (player0_data.score, player1_data.score) = match winner {
-                Winner::Player0 => (Some(winner_new), Some(looser_new)),
-                Winner::Player1 => (Some(looser_new), Some(winner_new)),
+            let (player0_new, player1_new) = match winner {
+                Winner::Player0 => (winner_new, looser_new),
+                Winner::Player1 => (looser_new, winner_new),
                 _ => return Ok(()),
             };
 
             // Write update of player data to runtime storage
-            <AuditorMap<T>>::insert(&player0, player0_data);
-            <AuditorMap<T>>::insert(&player1, player1_data);
+            match domain {
+                Some(domain) => {
+                    <DomainScores<T>>::insert(&player0, domain, player0_new);
+                    <DomainScores<T>>::insert(&player1, domain, player1_new);
+                }
+                None => {
+                    (player0_data.score, player1_data.score) =
+                        (Some(player0_new), Some(player1_new));
+                    <AuditorMap<T>>::insert(&player0, player0_data);
+                    <AuditorMap<T>>::insert(&player1, player1_data);
+                }
+            }
+
+            Self::record_game(&player0, &player1);
 
             // Emit GameResult event
             Self::deposit_event(Event::GameResult {
                 player0,
                 player1,
                 winner,
+                domain,
+                review_hash,
+                severity,
+            });
+
+            Ok(())
+        }
+
+        /// Opens a challenge of `defendant` by `challenger`, who bonds `ChallengeBond`. The defendant has
+        /// `ResponsePeriod` blocks to accept the challenge.
+        fn challenge(
+            challenger: T::AccountId,
+            defendant: T::AccountId,
+            context: ChallengeContext<T::Hash>,
+        ) -> DispatchResult {
+            ensure!(challenger != defendant, Error::<T>::SelfChallenge);
+            for auditor in [&challenger, &defendant] {
+                let data =
+                    <AuditorMap<T>>::try_get(auditor).map_err(|_| Error::<T>::UnknownAuditor)?;
+                ensure!(data.score.is_some(), Error::<T>::UnapprovedAuditor);
+            }
+            ensure!(!Self::is_leaving(&challenger), Error::<T>::AuditorLeaving);
+
+            T::Currency::reserve(&challenger, T::ChallengeBond::get())?;
+
+            let id = <NextChallengeId<T>>::get();
+            <NextChallengeId<T>>::put(id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?);
+
+            <Challenges<T>>::insert(
+                id,
+                Challenge {
+                    challenger: challenger.clone(),
+                    defendant: defendant.clone(),
+                    context,
+                    phase: ChallengePhase::AwaitingResponse,
+                    deadline: <frame_system::Pallet<T>>::block_number() + T::ResponsePeriod::get(),
+                    votes: BoundedVec::default(),
+                },
+            );
+            <OpenChallenges<T>>::mutate(&challenger, |count| *count = count.saturating_add(1));
+            <OpenChallenges<T>>::mutate(&defendant, |count| *count = count.saturating_add(1));
+
+            Self::deposit_event(Event::ChallengeOpened {
+                id,
+                challenger,
+                defendant,
             });
 
             Ok(())
@@ -0,0 +1,375 @@
+//! Storage migrations for `qdao-audit-pallet`
+//!
+//! Each migration module keeps a copy of `AuditorData` in the layout of its storage version, together with a storage
+//! alias of `AuditorMap` using this layout, so that every step can decode the entries written by the previous one.
+//!
+//! With the `try-runtime` feature, every migration checks that no auditor entry is lost. The checks are executed after
+//! all migrations of the runtime upgrade, so entries are counted by key instead of decoding them in a specific layout.
+
+use super::*;
+use frame_support::{
+    log,
+    pallet_prelude::*,
+    storage_alias,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_core::H256;
+use sp_std::marker::PhantomData;
+
+/// Returns the encoded number of auditor entries before the runtime upgrade
+#[cfg(feature = "try-runtime")]
+fn count_auditors<T: Config>() -> Result<Vec<u8>, &'static str> {
+    Ok((<crate::AuditorMap<T>>::iter_keys().count() as u32).encode())
+}
+
+/// Ensures that the on-chain storage version is at least `version` and that the number of auditor entries did not
+/// change. Once the current storage version is reached, all entries have to decode in the current layout.
+#[cfg(feature = "try-runtime")]
+fn ensure_auditors_migrated<T: Config>(state: Vec<u8>, version: u16) -> Result<(), &'static str> {
+    let auditors = u32::decode(&mut &state[..]).map_err(|_| "Invalid pre-upgrade state")?;
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+    ensure!(
+        on_chain_version >= version,
+        "Storage version was not updated"
+    );
+    ensure!(
+        <crate::AuditorMap<T>>::iter_keys().count() as u32 == auditors,
+        "Number of auditors changed during the migration"
+    );
+    if on_chain_version == Pallet::<T>::current_storage_version() {
+        ensure!(
+            <crate::AuditorMap<T>>::iter_keys()
+                .all(|who| <crate::AuditorMap<T>>::try_get(who).is_ok()),
+            "Auditor entry does not decode in the current layout"
+        );
+    }
+    Ok(())
+}
+
+/// Migration to storage version 1, which makes the number of required approvals configurable
+pub mod v1 {
+    use super::*;
+
+    /// `AuditorData` as it was stored before storage version 1, with three hard-coded approvals
+    #[derive(Encode, Decode)]
+    pub struct OldAuditorData<T: Config> {
+        pub score: Option<u32>,
+        pub profile_hash: H256,
+        pub approved_by: BoundedVec<T::AccountId, ConstU32<3>>,
+    }
+
+    /// `AuditorData` as it was stored in storage versions 1 and 2
+    #[derive(Encode, Decode)]
+    pub struct AuditorDataV1<T: Config> {
+        pub score: Option<u32>,
+        pub profile_hash: H256,
+        pub approved_by: BoundedVec<T::AccountId, T::RequiredApprovals>,
+    }
+
+    /// `AuditorMap` as it was stored in storage versions 1 and 2
+    #[storage_alias]
+    pub type AuditorMap<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        AuditorDataV1<T>,
+    >;
+
+    /// Rebounds `AuditorData::approved_by` from three to `RequiredApprovals` approvals. If the new bound is lower,
+    /// surplus approvals are dropped and pending auditors which now have `RequiredApprovals` approvals receive the
+    /// auditor status. Score weighted approvals are not taken into account, since the approvers' entries may not be
+    /// migrated yet.
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 0 {
+                log::info!(
+                    target: "runtime::qdao-audit",
+                    "Skipping migration to v1, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let required = T::RequiredApprovals::get();
+            let mut translated = 0u64;
+            <AuditorMap<T>>::translate_values::<OldAuditorData<T>, _>(|old| {
+                translated += 1;
+
+                let mut approved_by = old.approved_by.into_inner();
+                approved_by.truncate(required as usize);
+
+                let score = old.score.or_else(|| {
+                    (approved_by.len() as u32 >= required).then(T::InitialAuditorScore::get)
+                });
+
+                Some(AuditorDataV1::<T> {
+                    score,
+                    profile_hash: old.profile_hash,
+                    approved_by: approved_by.try_into().unwrap_or_default(),
+                })
+            });
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-audit",
+                "Migrated {} auditor entries to v1",
+                translated
+            );
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            count_auditors::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure_auditors_migrated::<T>(state, 1)
+        }
+    }
+}
+
+/// Migration to storage version 2, which introduces application deadlines for pending auditors
+pub mod v2 {
+    use super::*;
+
+    /// Sets the application deadline of all auditors which are still pending to `ApplicationExpiry` blocks after the
+    /// runtime upgrade, so that applications which were submitted before deadlines existed can expire as well.
+    pub struct MigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 1 {
+                log::info!(
+                    target: "runtime::qdao-audit",
+                    "Skipping migration to v2, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let deadline = <frame_system::Pallet<T>>::block_number() + T::ApplicationExpiry::get();
+            let mut reads = 0u64;
+            let mut writes = 0u64;
+            for (who, data) in <v1::AuditorMap<T>>::iter() {
+                reads += 1;
+                if data.score.is_none() && !Pallet::<T>::is_leaving(&who) {
+                    <ApplicationDeadline<T>>::insert(&who, deadline);
+                    writes += 1;
+                }
+            }
+
+            StorageVersion::new(2).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-audit",
+                "Set application deadlines for {} pending auditors",
+                writes
+            );
+
+            // Every entry additionally requires a read of `Unbonding`
+            T::DbWeight::get().reads_writes(2 * reads + 2, writes + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            count_auditors::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure_auditors_migrated::<T>(state, 2)
+        }
+    }
+}
+
+/// Migration to storage version 3, which adds specializations to `AuditorData`
+pub mod v3 {
+    use super::*;
+    use v1::AuditorDataV1;
+
+    /// `AuditorData` as it was stored in storage version 3
+    #[derive(Encode, Decode)]
+    pub struct AuditorDataV3<T: Config> {
+        pub score: Option<u32>,
+        pub profile_hash: H256,
+        pub approved_by: BoundedVec<T::AccountId, T::RequiredApprovals>,
+        pub specializations: BoundedVec<Domain, T::MaxSpecializations>,
+    }
+
+    /// `AuditorMap` as it was stored in storage version 3
+    #[storage_alias]
+    pub type AuditorMap<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        AuditorDataV3<T>,
+    >;
+
+    /// Adds empty specializations to all auditors
+    pub struct MigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 2 {
+                log::info!(
+                    target: "runtime::qdao-audit",
+                    "Skipping migration to v3, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            <AuditorMap<T>>::translate_values::<AuditorDataV1<T>, _>(|old| {
+                translated += 1;
+                Some(AuditorDataV3::<T> {
+                    score: old.score,
+                    profile_hash: old.profile_hash,
+                    approved_by: old.approved_by,
+                    specializations: BoundedVec::default(),
+                })
+            });
+
+            StorageVersion::new(3).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-audit",
+                "Migrated {} auditor entries to v3",
+                translated
+            );
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            count_auditors::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure_auditors_migrated::<T>(state, 3)
+        }
+    }
+}
+
+/// Migration to storage version 4, which adds the profile locator to `AuditorData`
+pub mod v4 {
+    use super::*;
+    use v3::AuditorDataV3;
+
+    /// Adds an empty profile locator to all auditors
+    pub struct MigrateToV4<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 3 {
+                log::info!(
+                    target: "runtime::qdao-audit",
+                    "Skipping migration to v4, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            <crate::AuditorMap<T>>::translate_values::<AuditorDataV3<T>, _>(|old| {
+                translated += 1;
+                Some(AuditorData {
+                    score: old.score,
+                    profile_hash: old.profile_hash,
+                    approved_by: old.approved_by,
+                    specializations: old.specializations,
+                    profile_locator: None,
+                })
+            });
+
+            StorageVersion::new(4).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-audit",
+                "Migrated {} auditor entries to v4",
+                translated
+            );
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            count_auditors::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure_auditors_migrated::<T>(state, 4)
+        }
+    }
+}
+
+/// Migration to storage version 5, which tracks the stake of every auditor in `AuditorStake`
+pub mod v5 {
+    use super::*;
+
+    /// Records the stake which was reserved by the pallet, which is `MinAuditorStake` for auditors which signed up and
+    /// nothing for auditors which were added in the genesis block. Genesis auditors are recognized by being approved
+    /// without approvals. Reservations of other pallets are not counted.
+    ///
+    /// Afterwards the stake required by the privileges of each auditor's score is reserved. Auditors who cannot afford
+    /// it keep their recorded stake and lose the privileges which are not covered until they top up their stake.
+    pub struct MigrateToV5<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 4 {
+                log::info!(
+                    target: "runtime::qdao-audit",
+                    "Skipping migration to v5, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut migrated = 0u64;
+            let mut under_staked = 0u64;
+            for (who, data) in <crate::AuditorMap<T>>::iter() {
+                migrated += 1;
+                let signed_up = data.score.is_none() || !data.approved_by.is_empty();
+                if signed_up {
+                    <AuditorStake<T>>::insert(&who, T::MinAuditorStake::get());
+                }
+                if Pallet::<T>::reserve_required_stake(&who).is_err() {
+                    under_staked += 1;
+                }
+            }
+
+            StorageVersion::new(5).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-audit",
+                "Set the stake of {} auditors, {} of them cannot afford the stake of their privileges",
+                migrated,
+                under_staked
+            );
+
+            T::DbWeight::get().reads_writes(3 * migrated + 1, 3 * migrated + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            count_auditors::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure_auditors_migrated::<T>(state, 5)
+        }
+    }
+}
@@ -1,14 +1,18 @@
-use crate::{self as qdao_pallet_dummy, AuditorData};
+use crate::{
+    self as qdao_pallet_dummy, AuditorData, AuditorDataOf, IdentityStatus, IdentityVerifier,
+};
 use frame_support::{
     parameter_types,
-    traits::{ConstU16, ConstU64, GenesisBuild},
+    traits::{ConstU16, ConstU32, ConstU64, GenesisBuild},
     BoundedVec,
 };
 use frame_system as system;
+use frame_system::EnsureRoot;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -29,6 +33,30 @@ frame_support::construct_runtime!(
 
 parameter_types! {
     pub const ExistentialDeposit: u64 = 1;
+    pub static RequiredApprovals: u32 = 3;
+    pub static ScoreWeightedApprovals: bool = false;
+    pub const MaxSpecializations: u32 = 3;
+    pub const MaxProfileLocatorLength: u32 = 256;
+    pub static RequireIdentity: bool = false;
+    pub static VerifiedIdentities: Vec<u64> = vec![];
+    pub static MaxGamesPerPeriod: u32 = 3;
+    pub static StakePerPrivilege: u64 = 0;
+    pub const ClaimedValueRatio: Perbill = Perbill::from_percent(50);
+    pub const EndorserFeeShare: Perbill = Perbill::from_percent(50);
+    pub const EndorsementSlash: Perbill = Perbill::from_percent(10);
+}
+
+/// Identities of the accounts in `VerifiedIdentities` are verified, all other accounts have no identity
+pub struct MockIdentityVerifier;
+
+impl IdentityVerifier<u64> for MockIdentityVerifier {
+    fn identity_status(who: &u64) -> IdentityStatus {
+        if VerifiedIdentities::get().contains(who) {
+            IdentityStatus::Verified
+        } else {
+            IdentityStatus::NotSet
+        }
+    }
 }
 
 impl system::Config for Test {
@@ -77,6 +105,31 @@ impl qdao_pallet_dummy::Config for Test {
     type MinAuditorStake = frame_support::traits::ConstU64<100>;
     type InitialAuditorScore = frame_support::traits::ConstU32<1000>;
     type MinimalApproverScore = frame_support::traits::ConstU32<2000>;
+    type UnbondingPeriod = ConstU64<10>;
+    type RequiredApprovals = RequiredApprovals;
+    type ScoreWeightedApprovals = ScoreWeightedApprovals;
+    type ApplicationExpiry = ConstU64<20>;
+    type MaxSpecializations = MaxSpecializations;
+    type MaxProfileLocatorLength = MaxProfileLocatorLength;
+    type IdentityVerifier = MockIdentityVerifier;
+    type RequireIdentity = RequireIdentity;
+    type PairCooldown = ConstU64<5>;
+    type AuditorCooldown = ConstU64<2>;
+    type RatingPeriod = ConstU64<100>;
+    type MaxGamesPerPeriod = MaxGamesPerPeriod;
+    type ChallengeBond = ConstU64<10>;
+    type ResponsePeriod = ConstU64<5>;
+    type VotingPeriod = ConstU64<10>;
+    type MaxJurors = ConstU32<2>;
+    type OracleOrigin = EnsureRoot<u64>;
+    type StakePerPrivilege = StakePerPrivilege;
+    type ClaimedValueRatio = ClaimedValueRatio;
+    type MaxEndorsers = ConstU32<2>;
+    type MinEndorsement = ConstU64<10>;
+    type EndorserFeeShare = EndorserFeeShare;
+    type EndorsementSlash = EndorsementSlash;
+    type MaxTeamMembers = ConstU32<3>;
+    type MaxAuditorCleanup = ConstU32<2>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -90,6 +143,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             (1, 100),
             (2, 100),
             (3, 100),
+            (4, 1000),
+            (5, 1000),
+            (6, 1000),
+            (7, 1000),
             (8, 10),
             (10, 100),
             (20, 100),
@@ -99,17 +156,23 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
     // We provide some initial Auditors which are able to approve other Auditors (reputation is high enough)
-    let auditor_data = AuditorData::<H256, u64> {
-        score: Some(2000),
-        profile_hash: H256::repeat_byte(1),
-        approved_by: BoundedVec::with_bounded_capacity(3),
-    };
+    let auditor_data =
+        AuditorData::<H256, u64, RequiredApprovals, MaxSpecializations, MaxProfileLocatorLength> {
+            score: Some(2000),
+            profile_hash: H256::repeat_byte(1),
+            approved_by: BoundedVec::default(),
+            specializations: BoundedVec::default(),
+            profile_locator: None,
+        };
     // We also want Auditors with a reputation score which is not high enough for Approvals, so that an attempted approval fails
-    let auditor_data_low_score = AuditorData::<H256, u64> {
-        score: Some(1000),
-        profile_hash: H256::repeat_byte(1),
-        approved_by: BoundedVec::with_bounded_capacity(3),
-    };
+    let auditor_data_low_score =
+        AuditorData::<H256, u64, RequiredApprovals, MaxSpecializations, MaxProfileLocatorLength> {
+            score: Some(1000),
+            profile_hash: H256::repeat_byte(1),
+            approved_by: BoundedVec::default(),
+            specializations: BoundedVec::default(),
+            profile_locator: None,
+        };
     qdao_pallet_dummy::GenesisConfig::<Test> {
         auditor_map: vec![
             (4, auditor_data.clone()),
@@ -122,3 +185,20 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .unwrap();
     t.into()
 }
+
+/// Builds genesis storage with only the given balances and genesis auditors
+pub fn new_genesis_ext(
+    balances: Vec<(u64, u64)>,
+    auditor_map: Vec<(u64, AuditorDataOf<Test>)>,
+) -> sp_io::TestExternalities {
+    let mut t = system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> { balances }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    qdao_pallet_dummy::GenesisConfig::<Test> { auditor_map }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    t.into()
+}
@@ -1,8 +1,34 @@
-use crate::{mock::*, AuditorMap, Error, Winner};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    migrations::{
+        v1::{AuditorDataV1, MigrateToV1, OldAuditorData},
+        v2::MigrateToV2,
+        v3::{AuditorDataV3, MigrateToV3},
+        v4::MigrateToV4,
+        v5::MigrateToV5,
+    },
+    mock::*,
+    ApplicationDeadline, AuditorMap, AuditorStake, AuditorStatus, ChallengeContext, ClaimedValue,
+    Domain, DomainScores, Error, FeeDistributor, GamesInPeriod, IdentityStatus, LastGame,
+    LastPairGame, LeaderboardEntry, PendingCleanup, Privilege, PrivilegeProvider, Severity,
+    StakeLedger, TeamInvites, TeamProvider, Unbonding, Winner,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::unhashed,
+    traits::{Get, GetStorageVersion, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+};
 use frame_system::ensure_signed;
 use sp_core::H256;
 
+/// Context of a challenge about a finding of medium severity, which is not linked to a review
+fn challenge(domain: Option<Domain>) -> ChallengeContext<H256> {
+    ChallengeContext {
+        review_hash: None,
+        severity: Severity::Medium,
+        domain,
+    }
+}
+
 #[test]
 fn sign_up_works() {
     new_test_ext().execute_with(|| {
@@ -12,7 +38,7 @@ fn sign_up_works() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash);
+        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash, None);
         let auditor_data = AuditorMap::<Test>::try_get(sender);
 
         // Then
@@ -34,10 +60,10 @@ fn sign_up_update_profile_works() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash);
+        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash, None);
         let auditor_data_before_update = AuditorMap::<Test>::try_get(sender);
         let update_profile_result =
-            AuditRepModule::update_profile(RuntimeOrigin::signed(1), hash_for_update);
+            AuditRepModule::update_profile(RuntimeOrigin::signed(1), hash_for_update, None);
         let auditor_data_after_update = AuditorMap::<Test>::try_get(sender);
 
         // Then
@@ -61,6 +87,92 @@ fn sign_up_update_profile_works() {
     });
 }
 
+#[test]
+fn profile_locator_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        let sender = ensure_signed(RuntimeOrigin::signed(1)).expect("Signing failed");
+        let locator =
+            b"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec();
+        let locator_for_update = b"https://example.com/profile.json".to_vec();
+
+        // When
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            Some(locator.clone())
+        ));
+        let auditor_data_before_update = AuditorMap::<Test>::get(sender);
+        assert_ok!(AuditRepModule::update_profile(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(2),
+            Some(locator_for_update.clone())
+        ));
+        let auditor_data_after_update = AuditorMap::<Test>::get(sender);
+        assert_ok!(AuditRepModule::update_profile(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(3),
+            None
+        ));
+        let auditor_data_after_removal = AuditorMap::<Test>::get(sender);
+
+        // Then
+        assert_eq!(
+            auditor_data_before_update
+                .expect("Auditor data not available")
+                .profile_locator
+                .map(|locator| locator.into_inner()),
+            Some(locator)
+        );
+        assert_eq!(
+            auditor_data_after_update
+                .expect("Auditor data after update not available")
+                .profile_locator
+                .map(|locator| locator.into_inner()),
+            Some(locator_for_update)
+        );
+        assert_eq!(
+            auditor_data_after_removal
+                .expect("Auditor data after removal not available")
+                .profile_locator,
+            None
+        );
+    });
+}
+
+#[test]
+fn invalid_profile_locator_fails() {
+    new_test_ext().execute_with(|| {
+        // Given
+        let too_long = vec![b'a'; MaxProfileLocatorLength::get() as usize + 1];
+
+        // When
+        let too_long_result = AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            Some(too_long.clone()),
+        );
+        let empty_result =
+            AuditRepModule::sign_up(RuntimeOrigin::signed(1), H256::repeat_byte(1), Some(vec![]));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(2),
+            H256::repeat_byte(1),
+            None
+        ));
+        let update_result = AuditRepModule::update_profile(
+            RuntimeOrigin::signed(2),
+            H256::repeat_byte(2),
+            Some(too_long),
+        );
+
+        // Then
+        assert_noop!(too_long_result, Error::<Test>::ProfileLocatorTooLong);
+        assert_noop!(empty_result, Error::<Test>::EmptyProfileLocator);
+        assert_noop!(update_result, Error::<Test>::ProfileLocatorTooLong);
+        assert!(!AuditorMap::<Test>::contains_key(1));
+    });
+}
+
 #[test]
 fn sign_up_fails_when_balance_too_low() {
     new_test_ext().execute_with(|| {
@@ -70,7 +182,7 @@ fn sign_up_fails_when_balance_too_low() {
         let hash = H256::repeat_byte(1);
 
         // When
-        let result = AuditRepModule::sign_up(auditor, hash);
+        let result = AuditRepModule::sign_up(auditor, hash, None);
 
         // Then
         assert_noop!(result, pallet_balances::Error::<Test>::InsufficientBalance);
@@ -87,12 +199,12 @@ fn sign_up_error_for_double_sign_up() {
         // When
         // Sign up Auditor, should work
         let auditor1_sign_up_result =
-            AuditRepModule::sign_up(auditor1.clone(), H256::repeat_byte(1));
+            AuditRepModule::sign_up(auditor1.clone(), H256::repeat_byte(1), None);
         //Sign up second (different) auditor, should also work
-        let auditor2_sign_up_result = AuditRepModule::sign_up(auditor2, H256::repeat_byte(1));
+        let auditor2_sign_up_result = AuditRepModule::sign_up(auditor2, H256::repeat_byte(1), None);
         // Sign up an already signed up auditor, should return an error
         let auditor_1_second_sign_up_result =
-            AuditRepModule::sign_up(auditor1, H256::repeat_byte(1));
+            AuditRepModule::sign_up(auditor1, H256::repeat_byte(1), None);
 
         // Then
         assert_ok!(auditor1_sign_up_result);
@@ -113,7 +225,7 @@ fn sign_up_cancellation_works() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash);
+        let sign_up_result = AuditRepModule::sign_up(RuntimeOrigin::signed(1), hash, None);
         let auditor_data_before_cancellation = AuditorMap::<Test>::try_get(sender);
         let cancellation_result = AuditRepModule::cancel_account(RuntimeOrigin::signed(1));
         let auditor_data_after_cancellation = AuditorMap::<Test>::try_get(sender);
@@ -121,7 +233,7 @@ fn sign_up_cancellation_works() {
             AuditRepModule::cancel_account(RuntimeOrigin::signed(1));
 
         // Then
-        // Check that new Auditor exists after creation and is successfully cancelled
+        // Check that new Auditor exists after creation and is marked as leaving after cancellation
         let auditor_data = auditor_data_before_cancellation
             .as_ref()
             .expect("Auditor data not available");
@@ -129,14 +241,193 @@ fn sign_up_cancellation_works() {
         assert_eq!(auditor_data.score, None);
         assert_eq!(auditor_data.profile_hash, hash);
         assert_ok!(cancellation_result);
-        assert_noop!(auditor_data_after_cancellation, ());
+        assert_eq!(auditor_data_after_cancellation.as_ref(), Ok(auditor_data));
+        assert_eq!(Unbonding::<Test>::get(sender), Some(10));
+        assert_eq!(Balances::reserved_balance(sender), 100);
         assert_noop!(
             cancellation_of_cancelled_account,
-            Error::<Test>::UnknownAuditor
+            Error::<Test>::AuditorLeaving
         );
     });
 }
 
+#[test]
+fn withdraw_unbonded_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor signed up and cancelled their account at block 1, the unbonding period is 10 blocks
+        System::set_block_number(1);
+        let sender = ensure_signed(RuntimeOrigin::signed(1)).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(1)));
+
+        // When
+        // Withdraw before and after the unbonding period is over
+        System::set_block_number(10);
+        let early_withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(1));
+        System::set_block_number(11);
+        let withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(1));
+        let second_withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(1));
+
+        // Then
+        // Check that the stake is only unreserved after the unbonding period and the account is removed
+        assert_noop!(early_withdraw_result, Error::<Test>::UnbondingPeriodNotOver);
+        assert_ok!(withdraw_result);
+        assert_eq!(Balances::reserved_balance(sender), 0);
+        assert_eq!(Balances::free_balance(sender), 100);
+        assert_noop!(AuditorMap::<Test>::try_get(sender), ());
+        assert_eq!(Unbonding::<Test>::get(sender), None);
+        assert_noop!(second_withdraw_result, Error::<Test>::NotUnbonding);
+    });
+}
+
+#[test]
+fn withdraw_unbonded_removes_all_auditor_data() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 4 and 5 played a rated game in the ink! domain, afterwards auditor 5 cancelled their account
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            5,
+            Winner::Player0,
+            challenge(Some(Domain::Ink)),
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+
+        // When
+        System::set_block_number(11);
+        let withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5));
+
+        // Then
+        // Nothing is left of auditor 5, including the game of the pair, while auditor 4 keeps their data
+        assert_ok!(withdraw_result);
+        assert_eq!(DomainScores::<Test>::iter_prefix(5).count(), 0);
+        assert_eq!(LastPairGame::<Test>::iter().count(), 0);
+        assert!(!LastGame::<Test>::contains_key(5));
+        assert!(!GamesInPeriod::<Test>::contains_key(5));
+        assert!(!ClaimedValue::<Test>::contains_key(5));
+        assert!(!AuditorStake::<Test>::contains_key(5));
+        assert_eq!(AuditRepModule::domain_score(&4, Domain::Ink), Some(2016));
+        assert!(LastGame::<Test>::contains_key(4));
+    });
+}
+
+#[test]
+fn auditor_data_is_removed_in_bounded_steps() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 5 played rated games against auditors 4, 6 and 7 and was invited to the team of auditor 4, which are
+        // four entries while at most two are removed per call
+        for (block, opponent) in [(1, 4), (3, 6), (5, 7)] {
+            System::set_block_number(block);
+            assert_ok!(AuditRepModule::game_result(
+                RuntimeOrigin::root(),
+                5,
+                opponent,
+                Winner::Draw,
+                challenge(None),
+            ));
+        }
+        assert_ok!(AuditRepModule::create_team(RuntimeOrigin::signed(4), 1));
+        assert_ok!(AuditRepModule::invite_to_team(
+            RuntimeOrigin::signed(4),
+            0,
+            5,
+            1
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+        System::set_block_number(15);
+
+        // When
+        assert_ok!(AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5)));
+        let sign_up_result =
+            AuditRepModule::sign_up(RuntimeOrigin::signed(5), H256::repeat_byte(1), None);
+        let clear_result = AuditRepModule::clear_auditor_data(RuntimeOrigin::signed(1), 5);
+        let second_clear_result = AuditRepModule::clear_auditor_data(RuntimeOrigin::signed(1), 5);
+
+        // Then
+        // The remaining entries are removed by the second call, afterwards the account can sign up again
+        assert_noop!(sign_up_result, Error::<Test>::CleanupPending);
+        assert_ok!(clear_result);
+        assert_noop!(second_clear_result, Error::<Test>::NothingToClean);
+        assert_eq!(LastPairGame::<Test>::iter().count(), 0);
+        assert_eq!(TeamInvites::<Test>::iter().count(), 0);
+        assert!(!PendingCleanup::<Test>::contains_key(5));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(5),
+            H256::repeat_byte(1),
+            None
+        ));
+    });
+}
+
+#[test]
+fn approvals_of_removed_auditors_do_not_count() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 4 and 5 approved applicant 1, afterwards auditor 5 left
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 1));
+        assert_ok!(AuditRepModule::approve_auditor(RuntimeOrigin::signed(5), 1));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+        System::set_block_number(11);
+        assert_ok!(AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5)));
+
+        // When
+        let approval_result = AuditRepModule::approve_auditor(RuntimeOrigin::signed(6), 1);
+
+        // Then
+        // The third approval only makes two valid approvals
+        assert_ok!(approval_result);
+        let applicant_data = AuditorMap::<Test>::get(1).expect("Auditor data not available");
+        assert_eq!(applicant_data.score, None);
+        assert_eq!(applicant_data.approved_by.into_inner(), vec![4, 6]);
+    });
+}
+
+#[test]
+fn leaving_auditor_cannot_approve_or_be_approved() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Approver 4 cancels their account, approvee 2 signs up and cancels again
+        let approvee_id = ensure_signed(RuntimeOrigin::signed(1)).expect("Signing failed");
+        let leaving_approvee_id = ensure_signed(RuntimeOrigin::signed(2)).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(2),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(4)));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(2)));
+
+        // When
+        let leaving_approver_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), approvee_id);
+        let leaving_approvee_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(5), leaving_approvee_id);
+
+        // Then
+        assert_noop!(leaving_approver_result, Error::<Test>::AuditorLeaving);
+        assert_noop!(leaving_approvee_result, Error::<Test>::AuditorLeaving);
+    });
+}
+
 #[test]
 fn approval_of_auditor_works() {
     new_test_ext().execute_with(|| {
@@ -149,7 +440,7 @@ fn approval_of_auditor_works() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(approvee, hash);
+        let sign_up_result = AuditRepModule::sign_up(approvee, hash, None);
         let approval_result = AuditRepModule::approve_auditor(approver.clone(), approvee_id);
         let double_approval_result = AuditRepModule::approve_auditor(approver, approvee_id);
         let auditor_data = AuditorMap::<Test>::try_get(approvee_id);
@@ -178,7 +469,7 @@ fn approval_with_low_reputation_fails() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(approvee, hash);
+        let sign_up_result = AuditRepModule::sign_up(approvee, hash, None);
         let approval_result = AuditRepModule::approve_auditor(approver_low_rep, approvee_id);
         let auditor_data = AuditorMap::<Test>::try_get(approvee_id);
 
@@ -206,7 +497,7 @@ fn approval_works() {
 
         // When
         // Sign up a new auditor, read the auditor_data from Storage
-        let sign_up_result = AuditRepModule::sign_up(approvee, hash);
+        let sign_up_result = AuditRepModule::sign_up(approvee, hash, None);
         let approval1_result = AuditRepModule::approve_auditor(approver1, approvee_id);
         let approval2_result = AuditRepModule::approve_auditor(approver2, approvee_id);
         let approval3_result = AuditRepModule::approve_auditor(approver3, approvee_id);
@@ -242,6 +533,7 @@ fn elo_score_update_works() {
             player0_id,
             player1_id,
             Winner::Player0,
+            challenge(None),
         );
         let player0_data = AuditorMap::<Test>::try_get(player0_id);
         let player1_data = AuditorMap::<Test>::try_get(player1_id);
@@ -260,3 +552,1315 @@ fn elo_score_update_works() {
         assert_eq!(player1_score, 1984);
     })
 }
+
+#[test]
+fn approval_threshold_is_configurable() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Only two approvals are required
+        RequiredApprovals::set(2);
+        let approvee = RuntimeOrigin::signed(1);
+        let approvee_id = ensure_signed(approvee.clone()).expect("Signing failed");
+
+        // When
+        let sign_up_result = AuditRepModule::sign_up(approvee, H256::repeat_byte(1), None);
+        let approval1_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), approvee_id);
+        let approval2_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(5), approvee_id);
+        let approval3_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(6), approvee_id);
+        let auditor_data = AuditorMap::<Test>::try_get(approvee_id);
+
+        // Then
+        // User should have the auditor status after two approvals, further approvals are rejected
+        assert_ok!(sign_up_result);
+        assert_ok!(approval1_result);
+        assert_ok!(approval2_result);
+        assert_noop!(approval3_result, Error::<Test>::AlreadyAuditor);
+        let auditor_data = auditor_data.as_ref().expect("Auditor data not available");
+        assert_eq!(auditor_data.approved_by.len(), 2);
+        assert_eq!(auditor_data.score, Some(1000));
+    });
+}
+
+#[test]
+fn score_weighted_approval_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Approvals are weighted by score and approver 4 has twice the minimal approver score
+        ScoreWeightedApprovals::set(true);
+        AuditorMap::<Test>::mutate(4, |data| {
+            data.as_mut().expect("Auditor data not available").score = Some(4000)
+        });
+        let approvee = RuntimeOrigin::signed(1);
+        let approvee_id = ensure_signed(approvee.clone()).expect("Signing failed");
+
+        // When
+        let sign_up_result = AuditRepModule::sign_up(approvee, H256::repeat_byte(1), None);
+        let approval1_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), approvee_id);
+        let score_after_first_approval = AuditorMap::<Test>::get(approvee_id).and_then(|d| d.score);
+        let approval2_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(5), approvee_id);
+        let auditor_data = AuditorMap::<Test>::try_get(approvee_id);
+
+        // Then
+        // Scores of 4000 and 2000 add up to the required 3 * 2000, so two approvals are sufficient
+        assert_ok!(sign_up_result);
+        assert_ok!(approval1_result);
+        assert_eq!(score_after_first_approval, None);
+        assert_ok!(approval2_result);
+        let auditor_data = auditor_data.as_ref().expect("Auditor data not available");
+        assert_eq!(auditor_data.approved_by.len(), 2);
+        assert_eq!(auditor_data.score, Some(1000));
+    });
+}
+
+#[test]
+fn migration_to_v1_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Two pending auditors stored with the old bound of three approvals, the new threshold is two approvals
+        StorageVersion::new(0).put::<AuditRepModule>();
+        RequiredApprovals::set(2);
+        let old_data = |approved_by: Vec<u64>| OldAuditorData::<Test> {
+            score: None,
+            profile_hash: H256::repeat_byte(1),
+            approved_by: approved_by.try_into().expect("Too many approvals"),
+        };
+        unhashed::put(
+            &AuditorMap::<Test>::hashed_key_for(1),
+            &old_data(vec![4, 5, 6]),
+        );
+        unhashed::put(&AuditorMap::<Test>::hashed_key_for(2), &old_data(vec![4]));
+
+        // When
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        // Then
+        // Surplus approvals are dropped, auditors with enough approvals receive the auditor status
+        let read_v1 = |who: u64| {
+            unhashed::get::<AuditorDataV1<Test>>(&AuditorMap::<Test>::hashed_key_for(who))
+                .expect("Auditor data not available")
+        };
+        let (auditor1, auditor2) = (read_v1(1), read_v1(2));
+        assert_eq!(auditor1.approved_by.into_inner(), vec![4, 5]);
+        assert_eq!(auditor1.score, Some(1000));
+        assert_eq!(auditor2.approved_by.into_inner(), vec![4]);
+        assert_eq!(auditor2.score, None);
+        assert_eq!(AuditRepModule::on_chain_storage_version(), 1);
+    });
+}
+
+/// All migrations of the pallet in the order in which the runtime executes them
+type AllMigrations = (
+    MigrateToV1<Test>,
+    MigrateToV2<Test>,
+    MigrateToV3<Test>,
+    MigrateToV4<Test>,
+    MigrateToV5<Test>,
+);
+
+/// Replaces the auditors of the genesis block by an approved and a pending auditor in the layout of storage
+/// version 0. Both auditors reserved `MinAuditorStake` on sign up.
+fn put_v0_fixture() {
+    StorageVersion::new(0).put::<AuditRepModule>();
+    AuditorMap::<Test>::drain().for_each(drop);
+    AuditorStake::<Test>::drain().for_each(drop);
+
+    let fixture = [
+        (
+            1,
+            OldAuditorData::<Test> {
+                score: Some(2000),
+                profile_hash: H256::repeat_byte(1),
+                approved_by: Default::default(),
+            },
+        ),
+        (
+            2,
+            OldAuditorData::<Test> {
+                score: None,
+                profile_hash: H256::repeat_byte(2),
+                approved_by: vec![4].try_into().expect("Too many approvals"),
+            },
+        ),
+    ];
+    for (who, data) in fixture {
+        unhashed::put(&AuditorMap::<Test>::hashed_key_for(who), &data);
+        assert_ok!(Balances::reserve(&who, 100));
+    }
+}
+
+#[test]
+fn all_migrations_work() {
+    new_test_ext().execute_with(|| {
+        // Given
+        System::set_block_number(1);
+        put_v0_fixture();
+
+        // When
+        #[cfg(feature = "try-runtime")]
+        let state = AllMigrations::pre_upgrade().expect("Pre-upgrade checks failed");
+        AllMigrations::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        AllMigrations::post_upgrade(state).expect("Post-upgrade checks failed");
+
+        // Then
+        // Both auditors decode in the current layout, the pending auditor received an application deadline
+        let approved = AuditorMap::<Test>::get(1).expect("Auditor data not available");
+        assert_eq!(approved.score, Some(2000));
+        assert!(approved.specializations.is_empty());
+        assert_eq!(approved.profile_locator, None);
+        let pending = AuditorMap::<Test>::get(2).expect("Auditor data not available");
+        assert_eq!(pending.score, None);
+        assert_eq!(pending.profile_hash, H256::repeat_byte(2));
+        assert_eq!(pending.approved_by.into_inner(), vec![4]);
+        assert_eq!(ApplicationDeadline::<Test>::get(2), Some(21));
+        assert_eq!(ApplicationDeadline::<Test>::get(1), None);
+        assert_eq!(AuditRepModule::auditor_stake(1), 100);
+        assert_eq!(AuditRepModule::auditor_stake(2), 100);
+        assert_eq!(AuditRepModule::on_chain_storage_version(), 5);
+    });
+}
+
+#[test]
+fn migrations_are_skipped_on_current_version() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // The current storage version is stored, like in the genesis block of a new chain
+        AuditRepModule::current_storage_version().put::<AuditRepModule>();
+        let mut auditors: Vec<_> = AuditorMap::<Test>::iter().collect();
+        auditors.sort_by_key(|(who, _)| *who);
+
+        // When
+        AllMigrations::on_runtime_upgrade();
+
+        // Then
+        // No entry is touched
+        let mut migrated: Vec<_> = AuditorMap::<Test>::iter().collect();
+        migrated.sort_by_key(|(who, _)| *who);
+        assert_eq!(migrated, auditors);
+        assert_eq!(AuditRepModule::on_chain_storage_version(), 5);
+        assert_eq!(ApplicationDeadline::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn migration_to_v4_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // A single auditor stored in the layout of storage version 3
+        StorageVersion::new(3).put::<AuditRepModule>();
+        AuditorMap::<Test>::drain().for_each(drop);
+        unhashed::put(
+            &AuditorMap::<Test>::hashed_key_for(1),
+            &AuditorDataV3::<Test> {
+                score: Some(1500),
+                profile_hash: H256::repeat_byte(1),
+                approved_by: vec![4, 5].try_into().expect("Too many approvals"),
+                specializations: vec![Domain::Ink]
+                    .try_into()
+                    .expect("Too many specializations"),
+            },
+        );
+
+        // When
+        MigrateToV4::<Test>::on_runtime_upgrade();
+
+        // Then
+        // The auditor keeps its data and has no profile locator
+        let auditor_data = AuditorMap::<Test>::get(1).expect("Auditor data not available");
+        assert_eq!(auditor_data.score, Some(1500));
+        assert_eq!(auditor_data.approved_by.into_inner(), vec![4, 5]);
+        assert_eq!(auditor_data.specializations.into_inner(), vec![Domain::Ink]);
+        assert_eq!(auditor_data.profile_locator, None);
+        assert_eq!(AuditRepModule::on_chain_storage_version(), 4);
+    });
+}
+
+#[test]
+fn revoke_approval_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        let approvee = RuntimeOrigin::signed(1);
+        let approvee_id = ensure_signed(approvee.clone()).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            approvee,
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::approve_auditor(
+            RuntimeOrigin::signed(4),
+            approvee_id
+        ));
+
+        // When
+        let revoke_result = AuditRepModule::revoke_approval(RuntimeOrigin::signed(4), approvee_id);
+        let auditor_data = AuditorMap::<Test>::try_get(approvee_id);
+        let double_revoke_result =
+            AuditRepModule::revoke_approval(RuntimeOrigin::signed(4), approvee_id);
+        let approve_again_result =
+            AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), approvee_id);
+
+        // Then
+        // The approval is removed and can be given again
+        assert_ok!(revoke_result);
+        let auditor_data = auditor_data.as_ref().expect("Auditor data not available");
+        assert!(auditor_data.approved_by.is_empty());
+        assert_noop!(double_revoke_result, Error::<Test>::NotApproved);
+        assert_ok!(approve_again_result);
+    });
+}
+
+#[test]
+fn revoke_approval_of_approved_auditor_fails() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 1 received all three approvals
+        let approvee = RuntimeOrigin::signed(1);
+        let approvee_id = ensure_signed(approvee.clone()).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            approvee,
+            H256::repeat_byte(1),
+            None
+        ));
+        for approver in [4, 5, 6] {
+            assert_ok!(AuditRepModule::approve_auditor(
+                RuntimeOrigin::signed(approver),
+                approvee_id
+            ));
+        }
+
+        // When
+        let revoke_result = AuditRepModule::revoke_approval(RuntimeOrigin::signed(4), approvee_id);
+
+        // Then
+        assert_noop!(revoke_result, Error::<Test>::AlreadyAuditor);
+        assert_eq!(ApplicationDeadline::<Test>::get(approvee_id), None);
+    });
+}
+
+#[test]
+fn expire_application_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor signed up at block 1, applications expire after 20 blocks
+        System::set_block_number(1);
+        let applicant = RuntimeOrigin::signed(1);
+        let applicant_id = ensure_signed(applicant.clone()).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            applicant,
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        System::set_block_number(20);
+        let early_expire_result =
+            AuditRepModule::expire_application(RuntimeOrigin::signed(2), applicant_id);
+        System::set_block_number(21);
+        let expire_result =
+            AuditRepModule::expire_application(RuntimeOrigin::signed(2), applicant_id);
+
+        // Then
+        // The application can only be expired after the deadline, afterwards the stake is returned
+        assert_noop!(early_expire_result, Error::<Test>::ApplicationNotExpired);
+        assert_ok!(expire_result);
+        assert_noop!(AuditorMap::<Test>::try_get(applicant_id), ());
+        assert_eq!(ApplicationDeadline::<Test>::get(applicant_id), None);
+        assert_eq!(Balances::free_balance(applicant_id), 100);
+    });
+}
+
+#[test]
+fn expire_application_of_approved_auditor_fails() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 is an approved auditor from genesis
+        System::set_block_number(100);
+
+        // When
+        let expire_result = AuditRepModule::expire_application(RuntimeOrigin::signed(1), 4);
+
+        // Then
+        assert_noop!(expire_result, Error::<Test>::NoPendingApplication);
+    });
+}
+
+#[test]
+fn update_specializations_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        let sender = ensure_signed(RuntimeOrigin::signed(1)).expect("Signing failed");
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        let update_result = AuditRepModule::update_specializations(
+            RuntimeOrigin::signed(1),
+            vec![Domain::Solidity, Domain::Substrate],
+        );
+        let auditor_data = AuditorMap::<Test>::try_get(sender);
+        let duplicate_result = AuditRepModule::update_specializations(
+            RuntimeOrigin::signed(1),
+            vec![Domain::Ink, Domain::Ink],
+        );
+        let unknown_auditor_result =
+            AuditRepModule::update_specializations(RuntimeOrigin::signed(2), vec![Domain::Ink]);
+
+        // Then
+        // Specializations are stored sorted
+        assert_ok!(update_result);
+        let auditor_data = auditor_data.as_ref().expect("Auditor data not available");
+        assert_eq!(
+            auditor_data.specializations.clone().into_inner(),
+            vec![Domain::Substrate, Domain::Solidity]
+        );
+        assert_noop!(duplicate_result, Error::<Test>::DuplicateSpecialization);
+        assert_noop!(unknown_auditor_result, Error::<Test>::UnknownAuditor);
+    });
+}
+
+#[test]
+fn domain_game_result_only_updates_domain_score() {
+    new_test_ext().execute_with(|| {
+        // When
+        // Submit a game result in the ink! domain, initially both players have score 2000, Player0 wins
+        let game_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            5,
+            Winner::Player0,
+            challenge(Some(Domain::Ink)),
+        );
+
+        // Then
+        // The domain scores are updated, while the global and other domain scores stay the same
+        assert_ok!(game_result);
+        assert_eq!(AuditRepModule::domain_score(&4, Domain::Ink), Some(2016));
+        assert_eq!(AuditRepModule::domain_score(&5, Domain::Ink), Some(1984));
+        assert_eq!(
+            AuditRepModule::domain_score(&4, Domain::Solidity),
+            Some(2000)
+        );
+        assert_eq!(AuditorMap::<Test>::get(4).and_then(|d| d.score), Some(2000));
+        assert_eq!(AuditorMap::<Test>::get(5).and_then(|d| d.score), Some(2000));
+    });
+}
+
+#[test]
+fn leaderboard_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 5 won a game against auditor 6, auditor 1 signed up and is pending, auditor 4 is leaving
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            5,
+            6,
+            Winner::Player0,
+            challenge(None),
+        ));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(4)));
+        let accounts = |entries: Vec<LeaderboardEntry<u64>>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.account)
+                .collect::<Vec<_>>()
+        };
+
+        // When
+        let all = AuditRepModule::leaderboard(AuditorStatus::Any, None, 0, 10);
+        let approved = AuditRepModule::leaderboard(AuditorStatus::Approved, None, 0, 10);
+        let pending = AuditRepModule::leaderboard(AuditorStatus::Pending, None, 0, 10);
+        let second_page = AuditRepModule::leaderboard(AuditorStatus::Approved, None, 1, 1);
+
+        // Then
+        // Auditors are ranked by score, pending auditors are listed last and leaving auditors are not listed
+        assert_eq!(accounts(all), vec![5, 6, 7, 1]);
+        assert_eq!(accounts(approved), vec![5, 6, 7]);
+        assert_eq!(accounts(pending), vec![1]);
+        assert_eq!(accounts(second_page), vec![6]);
+    });
+}
+
+#[test]
+fn leaderboard_by_specialization_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 5 and 6 are specialized in ink!, auditor 6 won a game in the ink! domain
+        for auditor in [5, 6] {
+            assert_ok!(AuditRepModule::update_specializations(
+                RuntimeOrigin::signed(auditor),
+                vec![Domain::Ink]
+            ));
+        }
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            5,
+            6,
+            Winner::Player1,
+            challenge(Some(Domain::Ink)),
+        ));
+
+        // When
+        let ink = AuditRepModule::leaderboard(AuditorStatus::Approved, Some(Domain::Ink), 0, 10);
+        let solidity =
+            AuditRepModule::leaderboard(AuditorStatus::Approved, Some(Domain::Solidity), 0, 10);
+
+        // Then
+        // Auditors are ranked by their domain score
+        assert_eq!(
+            ink.iter()
+                .map(|entry| (entry.account, entry.score))
+                .collect::<Vec<_>>(),
+            vec![(6, Some(2016)), (5, Some(1984))]
+        );
+        assert!(solidity.is_empty());
+    });
+}
+
+#[test]
+fn approval_requires_verified_identity() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Identities are required, only the identity of auditor 2 is verified
+        RequireIdentity::set(true);
+        VerifiedIdentities::set(vec![2]);
+        for applicant in [1, 2] {
+            assert_ok!(AuditRepModule::sign_up(
+                RuntimeOrigin::signed(applicant),
+                H256::repeat_byte(1),
+                None
+            ));
+        }
+
+        // When
+        let unverified_result = AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 1);
+        let verified_result = AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 2);
+        let pending = AuditRepModule::leaderboard(AuditorStatus::Pending, None, 0, 10);
+
+        // Then
+        // Only the auditor with a verified identity can be approved, the judgement is part of the leaderboard
+        assert_noop!(unverified_result, Error::<Test>::IdentityNotVerified);
+        assert_ok!(verified_result);
+        assert_eq!(
+            pending
+                .into_iter()
+                .map(|entry| (entry.account, entry.identity))
+                .collect::<Vec<_>>(),
+            vec![(1, IdentityStatus::NotSet), (2, IdentityStatus::Verified)]
+        );
+    });
+}
+
+#[test]
+fn game_result_is_weighted_by_severity() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // All auditors have a score of 2000 initially, the challenge is linked to a review
+        System::set_block_number(1);
+        let review_hash = H256::repeat_byte(9);
+        let context = |severity| ChallengeContext {
+            review_hash: Some(review_hash),
+            severity,
+            domain: None,
+        };
+
+        // When
+        let low_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            5,
+            Winner::Player0,
+            context(Severity::Low),
+        );
+        let critical_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            6,
+            7,
+            Winner::Player1,
+            context(Severity::Critical),
+        );
+
+        // Then
+        // The rating change grows with the severity and the event links back to the review
+        assert_ok!(low_result);
+        assert_ok!(critical_result);
+        assert_eq!(AuditorMap::<Test>::get(4).and_then(|d| d.score), Some(2008));
+        assert_eq!(AuditorMap::<Test>::get(5).and_then(|d| d.score), Some(1992));
+        assert_eq!(AuditorMap::<Test>::get(6).and_then(|d| d.score), Some(1936));
+        assert_eq!(AuditorMap::<Test>::get(7).and_then(|d| d.score), Some(1064));
+        System::assert_last_event(
+            crate::Event::<Test>::GameResult {
+                player0: 6,
+                player1: 7,
+                winner: Winner::Player1,
+                domain: None,
+                review_hash: Some(review_hash),
+                severity: Severity::Critical,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn game_cooldowns_work() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 4 and 5 played a rated game in block 1, the pair cooldown is 5 blocks, the auditor cooldown 2 blocks
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            5,
+            Winner::Player0,
+            challenge(None),
+        ));
+
+        // When
+        System::set_block_number(2);
+        let auditor_cooldown_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            6,
+            Winner::Player0,
+            challenge(None),
+        );
+        System::set_block_number(3);
+        let other_auditor_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            6,
+            Winner::Player0,
+            challenge(None),
+        );
+        System::set_block_number(5);
+        let pair_cooldown_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            5,
+            4,
+            Winner::Player0,
+            challenge(None),
+        );
+        System::set_block_number(6);
+        let after_cooldown_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            5,
+            4,
+            Winner::Player0,
+            challenge(None),
+        );
+
+        // Then
+        // The pair cooldown applies regardless of the order of the players
+        assert_noop!(auditor_cooldown_result, Error::<Test>::AuditorCooldown);
+        assert_ok!(other_auditor_result);
+        assert_noop!(pair_cooldown_result, Error::<Test>::PairCooldown);
+        assert_ok!(after_cooldown_result);
+    });
+}
+
+#[test]
+fn games_per_period_are_limited() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 played the maximum of two rated games in the first rating period of 100 blocks
+        MaxGamesPerPeriod::set(2);
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            5,
+            Winner::Player0,
+            challenge(None),
+        ));
+        System::set_block_number(10);
+        assert_ok!(AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            4,
+            6,
+            Winner::Player0,
+            challenge(None),
+        ));
+
+        // When
+        System::set_block_number(20);
+        let limited_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            7,
+            4,
+            Winner::Player0,
+            challenge(None),
+        );
+        System::set_block_number(100);
+        let next_period_result = AuditRepModule::game_result(
+            RuntimeOrigin::root(),
+            7,
+            4,
+            Winner::Player0,
+            challenge(None),
+        );
+
+        // Then
+        assert_noop!(limited_result, Error::<Test>::TooManyGames);
+        assert_ok!(next_period_result);
+    });
+}
+
+#[test]
+fn privilege_thresholds_work() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 4 and 5 have a score of 2000, auditor 7 a score of 1000, auditor 5 is leaving
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        let set_result = AuditRepModule::set_privilege_threshold(
+            RuntimeOrigin::root(),
+            Privilege::ClaimReview,
+            Some(1000),
+        );
+        let unauthorized_result = AuditRepModule::set_privilege_threshold(
+            RuntimeOrigin::signed(4),
+            Privilege::ClaimReview,
+            Some(0),
+        );
+        assert_ok!(AuditRepModule::set_privilege_threshold(
+            RuntimeOrigin::root(),
+            Privilege::ApproveAuditors,
+            Some(2500),
+        ));
+        let approve_result = AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 1);
+
+        // Then
+        // Thresholds which are not set default to `MinimalApproverScore`
+        assert_ok!(set_result);
+        assert_noop!(unauthorized_result, sp_runtime::DispatchError::BadOrigin);
+        assert!(AuditRepModule::has_privilege(&7, Privilege::ClaimReview));
+        assert!(!AuditRepModule::has_privilege(&7, Privilege::JuryDuty));
+        assert!(AuditRepModule::has_privilege(&4, Privilege::JuryDuty));
+        assert!(!AuditRepModule::has_privilege(&5, Privilege::ClaimReview));
+        assert!(!AuditRepModule::has_privilege(&1, Privilege::ClaimReview));
+        assert!(!AuditRepModule::has_privilege(
+            &4,
+            Privilege::ApproveAuditors
+        ));
+        assert_noop!(approve_result, Error::<Test>::ReputationTooLow);
+    });
+}
+
+#[test]
+fn challenge_resolved_by_jury_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 challenges auditor 5 and auditor 5 accepts, both bond 10
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::open_challenge(
+            RuntimeOrigin::signed(4),
+            5,
+            challenge(None)
+        ));
+        System::set_block_number(2);
+        assert_ok!(AuditRepModule::accept_challenge(
+            RuntimeOrigin::signed(5),
+            0
+        ));
+
+        // When
+        // Auditor 6 votes for the challenger, auditor 7 has a score too low for jury duty
+        let vote_result = AuditRepModule::vote(RuntimeOrigin::signed(6), 0, Winner::Player0);
+        let double_vote_result = AuditRepModule::vote(RuntimeOrigin::signed(6), 0, Winner::Player1);
+        let party_vote_result = AuditRepModule::vote(RuntimeOrigin::signed(4), 0, Winner::Player0);
+        let low_score_vote_result =
+            AuditRepModule::vote(RuntimeOrigin::signed(7), 0, Winner::Player1);
+        let early_resolve_result = AuditRepModule::resolve_challenge(RuntimeOrigin::signed(1), 0);
+        System::set_block_number(13);
+        let resolve_result = AuditRepModule::resolve_challenge(RuntimeOrigin::signed(1), 0);
+
+        // Then
+        // The challenger wins, receives the defendant's bond and the scores are updated
+        assert_ok!(vote_result);
+        assert_noop!(double_vote_result, Error::<Test>::AlreadyVoted);
+        assert_noop!(party_vote_result, Error::<Test>::PartyCannotVote);
+        assert_noop!(low_score_vote_result, Error::<Test>::NotJuror);
+        assert_noop!(
+            early_resolve_result,
+            Error::<Test>::ChallengeDeadlineNotPassed
+        );
+        assert_ok!(resolve_result);
+        assert_eq!(Balances::free_balance(4), 910);
+        assert_eq!(Balances::free_balance(5), 890);
+        assert_eq!(Balances::reserved_balance(4), 0);
+        assert_eq!(Balances::reserved_balance(5), 0);
+        assert_eq!(AuditorMap::<Test>::get(4).and_then(|d| d.score), Some(2016));
+        assert_eq!(AuditorMap::<Test>::get(5).and_then(|d| d.score), Some(1984));
+        assert!(AuditRepModule::challenges(0).is_none());
+        System::assert_last_event(
+            crate::Event::<Test>::ChallengeResolved {
+                id: 0,
+                winner: Winner::Player0,
+                rated: true,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn unanswered_challenge_is_won_by_challenger() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // The response period of 5 blocks ends in block 6
+        System::set_block_number(1);
+        assert_noop!(
+            AuditRepModule::open_challenge(RuntimeOrigin::signed(4), 4, challenge(None)),
+            Error::<Test>::SelfChallenge
+        );
+        assert_noop!(
+            AuditRepModule::open_challenge(RuntimeOrigin::signed(4), 1, challenge(None)),
+            Error::<Test>::UnknownAuditor
+        );
+        assert_ok!(AuditRepModule::open_challenge(
+            RuntimeOrigin::signed(4),
+            5,
+            challenge(None)
+        ));
+
+        // When
+        System::set_block_number(7);
+        let accept_result = AuditRepModule::accept_challenge(RuntimeOrigin::signed(5), 0);
+        let resolve_result = AuditRepModule::resolve_challenge(RuntimeOrigin::signed(1), 0);
+
+        // Then
+        // The challenger wins by default and gets the bond back, the defendant did not bond anything
+        assert_noop!(accept_result, Error::<Test>::ChallengeDeadlinePassed);
+        assert_ok!(resolve_result);
+        assert_eq!(Balances::free_balance(4), 900);
+        assert_eq!(Balances::free_balance(5), 900);
+        assert_eq!(AuditorMap::<Test>::get(4).and_then(|d| d.score), Some(2016));
+        assert_eq!(AuditorMap::<Test>::get(5).and_then(|d| d.score), Some(1984));
+    });
+}
+
+#[test]
+fn challenge_is_not_settled_if_rating_fails() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 challenges auditor 5, who is no longer approved when the challenge is resolved
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::open_challenge(
+            RuntimeOrigin::signed(4),
+            5,
+            challenge(None)
+        ));
+        AuditorMap::<Test>::mutate(5, |data| {
+            if let Some(data) = data {
+                data.score = None;
+            }
+        });
+        let reserved = Balances::reserved_balance(4);
+        System::set_block_number(7);
+
+        // When
+        let resolve_result = AuditRepModule::resolve_challenge(RuntimeOrigin::signed(1), 0);
+
+        // Then
+        // The error is returned and the bond of the challenger stays reserved
+        assert_noop!(resolve_result, Error::<Test>::UnapprovedAuditor);
+        assert_eq!(Balances::reserved_balance(4), reserved);
+        assert!(AuditRepModule::challenges(0).is_some());
+    });
+}
+
+#[test]
+fn oracle_resolve_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 challenges auditor 5, who cancels their account
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::open_challenge(
+            RuntimeOrigin::signed(4),
+            5,
+            challenge(None)
+        ));
+        assert_ok!(AuditRepModule::accept_challenge(
+            RuntimeOrigin::signed(5),
+            0
+        ));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+        System::set_block_number(11);
+
+        // When
+        let withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5));
+        let unauthorized_result =
+            AuditRepModule::oracle_resolve(RuntimeOrigin::signed(6), 0, Winner::Player1);
+        let oracle_result =
+            AuditRepModule::oracle_resolve(RuntimeOrigin::root(), 0, Winner::Player1);
+
+        // Then
+        // The defendant wins and can withdraw once the challenge is resolved
+        assert_noop!(withdraw_result, Error::<Test>::OpenChallenge);
+        assert_noop!(unauthorized_result, sp_runtime::DispatchError::BadOrigin);
+        assert_ok!(oracle_result);
+        assert_eq!(Balances::free_balance(4), 890);
+        assert_eq!(Balances::free_balance(5), 910);
+        assert_ok!(AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5)));
+    });
+}
+
+#[test]
+fn stake_can_be_topped_up_and_reduced() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 reserved `MinAuditorStake` in the genesis block
+        assert_eq!(AuditRepModule::auditor_stake(4), 100);
+
+        // When
+        let reduce_below_minimum_result = AuditRepModule::reduce_stake(RuntimeOrigin::signed(4), 1);
+        let unknown_result = AuditRepModule::top_up_stake(RuntimeOrigin::signed(1), 50);
+        let top_up_result = AuditRepModule::top_up_stake(RuntimeOrigin::signed(4), 50);
+        let reduce_result = AuditRepModule::reduce_stake(RuntimeOrigin::signed(4), 30);
+        let reduce_too_much_result = AuditRepModule::reduce_stake(RuntimeOrigin::signed(4), 200);
+
+        // Then
+        // Stake above the required stake can be unreserved again
+        assert_noop!(
+            reduce_below_minimum_result,
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(unknown_result, Error::<Test>::UnknownAuditor);
+        assert_ok!(top_up_result);
+        assert_ok!(reduce_result);
+        assert_noop!(reduce_too_much_result, Error::<Test>::InsufficientStake);
+        assert_eq!(AuditRepModule::auditor_stake(4), 120);
+        assert_eq!(Balances::reserved_balance(4), 120);
+        assert_eq!(Balances::free_balance(4), 880);
+    });
+}
+
+#[test]
+fn privileges_require_stake() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 has a score of 2000, which unlocks all privileges
+        StakePerPrivilege::set(25);
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        let required_stake = AuditRepModule::required_stake(&4, 0);
+        let approve_result = AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 1);
+        let has_privilege_before = AuditRepModule::has_privilege(&4, Privilege::ClaimReview);
+        assert_ok!(AuditRepModule::top_up_stake(RuntimeOrigin::signed(4), 100));
+
+        // Then
+        // Every unlocked privilege requires `StakePerPrivilege` on top of `MinAuditorStake`
+        assert_eq!(required_stake, 200);
+        assert_noop!(approve_result, Error::<Test>::InsufficientStake);
+        assert!(!has_privilege_before);
+        assert!(AuditRepModule::has_privilege(&4, Privilege::ClaimReview));
+        assert_ok!(AuditRepModule::approve_auditor(RuntimeOrigin::signed(4), 1));
+    });
+}
+
+#[test]
+fn genesis_and_approved_auditors_reserve_the_required_stake() {
+    // Given
+    // The score of 2000 of the genesis auditors unlocks all privileges, which require stake from genesis on
+    StakePerPrivilege::set(50);
+    new_test_ext().execute_with(|| {
+        assert_ok!(AuditRepModule::set_privilege_threshold(
+            RuntimeOrigin::root(),
+            Privilege::ClaimReview,
+            Some(1000)
+        ));
+        assert_ok!(Balances::transfer(RuntimeOrigin::signed(7), 1, 100));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        let approve_results: Vec<_> = [4, 5, 6]
+            .into_iter()
+            .map(|approver| AuditRepModule::approve_auditor(RuntimeOrigin::signed(approver), 1))
+            .collect();
+
+        // Then
+        // The initial score of auditor 1 unlocks `ClaimReview`, whose stake is reserved on approval
+        assert_eq!(AuditRepModule::auditor_stake(4), 300);
+        assert_eq!(Balances::reserved_balance(4), 300);
+        for approve_result in approve_results {
+            assert_ok!(approve_result);
+        }
+        assert_eq!(AuditRepModule::auditor_stake(1), 150);
+        assert_eq!(Balances::reserved_balance(1), 150);
+        assert!(AuditRepModule::has_privilege(&1, Privilege::ClaimReview));
+    });
+}
+
+#[test]
+fn approval_does_not_require_the_stake_of_privileges() {
+    // Given
+    // The initial score unlocks `ClaimReview`, which requires 50 more than the applicant can afford
+    StakePerPrivilege::set(50);
+    new_test_ext().execute_with(|| {
+        assert_ok!(AuditRepModule::set_privilege_threshold(
+            RuntimeOrigin::root(),
+            Privilege::ClaimReview,
+            Some(1000)
+        ));
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+
+        // When
+        let approve_results: Vec<_> = [4, 5, 6]
+            .into_iter()
+            .map(|approver| AuditRepModule::approve_auditor(RuntimeOrigin::signed(approver), 1))
+            .collect();
+
+        // Then
+        // The applicant is approved, but only receives the privilege after topping up the stake
+        for approve_result in approve_results {
+            assert_ok!(approve_result);
+        }
+        assert_eq!(AuditorMap::<Test>::get(1).and_then(|d| d.score), Some(1000));
+        assert_eq!(AuditRepModule::auditor_stake(1), 100);
+        assert!(!AuditRepModule::has_privilege(&1, Privilege::ClaimReview));
+        assert_ok!(Balances::transfer(RuntimeOrigin::signed(7), 1, 50));
+        assert_ok!(AuditRepModule::top_up_stake(RuntimeOrigin::signed(1), 50));
+        assert!(AuditRepModule::has_privilege(&1, Privilege::ClaimReview));
+    });
+}
+
+#[test]
+fn genesis_applications_expire() {
+    // Given
+    // Auditor 1 is a pending auditor in the genesis config
+    new_genesis_ext(vec![(1, 100)], vec![(1, Default::default())]).execute_with(|| {
+        // When
+        System::set_block_number(19);
+        let early_expire_result = AuditRepModule::expire_application(RuntimeOrigin::signed(2), 1);
+        System::set_block_number(20);
+        let expire_result = AuditRepModule::expire_application(RuntimeOrigin::signed(2), 1);
+
+        // Then
+        // The application expires `ApplicationExpiry` blocks after genesis and the stake is returned
+        assert_noop!(early_expire_result, Error::<Test>::ApplicationNotExpired);
+        assert_ok!(expire_result);
+        assert_eq!(Balances::free_balance(1), 100);
+    });
+}
+
+#[test]
+#[should_panic(expected = "cannot reserve the required stake")]
+fn genesis_auditors_have_to_afford_the_stake() {
+    // Auditor 1 cannot afford `MinAuditorStake`
+    new_genesis_ext(vec![(1, 50)], vec![(1, Default::default())]);
+}
+
+#[test]
+fn claimed_value_requires_stake() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Half of the claimed value has to be covered by stake
+        assert_ok!(AuditRepModule::add_claimed_value(&4, 10));
+
+        // When
+        let claim_result = AuditRepModule::add_claimed_value(&4, 10);
+        assert_ok!(AuditRepModule::top_up_stake(RuntimeOrigin::signed(4), 10));
+        let claim_after_top_up_result = AuditRepModule::add_claimed_value(&4, 10);
+        let reduce_result = AuditRepModule::reduce_stake(RuntimeOrigin::signed(4), 10);
+        AuditRepModule::release_claimed_value(&4, 20);
+
+        // Then
+        // The stake is locked while the value is claimed
+        assert_noop!(claim_result, Error::<Test>::InsufficientStake);
+        assert_ok!(claim_after_top_up_result);
+        assert_noop!(reduce_result, Error::<Test>::InsufficientStake);
+        assert_eq!(AuditRepModule::claimed_value(4), 0);
+        assert_ok!(AuditRepModule::reduce_stake(RuntimeOrigin::signed(4), 10));
+    });
+}
+
+#[test]
+fn migration_to_v5_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 1 signed up and has another reservation, auditors 2 and 4 were added in the genesis block without
+        // reserving anything. Their score unlocks all privileges, which require 300 but auditor 2 only has 100.
+        StorageVersion::new(4).put::<AuditRepModule>();
+        assert_ok!(AuditRepModule::sign_up(
+            RuntimeOrigin::signed(1),
+            H256::repeat_byte(1),
+            None
+        ));
+        assert_ok!(Balances::reserve(&1, 50));
+        AuditorMap::<Test>::insert(
+            2,
+            AuditorMap::<Test>::get(4).expect("Auditor data not available"),
+        );
+        AuditorStake::<Test>::drain().for_each(drop);
+        Balances::unreserve(&4, 100);
+        StakePerPrivilege::set(50);
+
+        // When
+        MigrateToV5::<Test>::on_runtime_upgrade();
+
+        // Then
+        // Only the reservations of the pallet are counted, the stake required by the score is reserved if possible
+        assert_eq!(AuditRepModule::auditor_stake(1), 100);
+        assert_eq!(Balances::reserved_balance(1), 150);
+        assert_eq!(AuditRepModule::auditor_stake(2), 0);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(AuditRepModule::auditor_stake(4), 300);
+        assert_eq!(Balances::reserved_balance(4), 300);
+        assert_eq!(AuditRepModule::on_chain_storage_version(), 5);
+    });
+}
+
+#[test]
+fn endorsement_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 is approved, account 8 is not an auditor and auditors endorse at most two accounts in the mock
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(1), 4, 40));
+
+        // When
+        let top_up_result = AuditRepModule::endorse(RuntimeOrigin::signed(1), 4, 10);
+        let too_low_result = AuditRepModule::endorse(RuntimeOrigin::signed(2), 4, 5);
+        let self_result = AuditRepModule::endorse(RuntimeOrigin::signed(5), 5, 10);
+        let unknown_result = AuditRepModule::endorse(RuntimeOrigin::signed(2), 8, 10);
+        let second_result = AuditRepModule::endorse(RuntimeOrigin::signed(2), 4, 20);
+        let too_many_result = AuditRepModule::endorse(RuntimeOrigin::signed(3), 4, 20);
+        let not_endorsed_result = AuditRepModule::withdraw_endorsement(RuntimeOrigin::signed(3), 4);
+        let withdraw_result = AuditRepModule::withdraw_endorsement(RuntimeOrigin::signed(1), 4);
+
+        // Then
+        // Endorsements are reserved until they are withdrawn
+        assert_ok!(top_up_result);
+        assert_noop!(too_low_result, Error::<Test>::EndorsementTooLow);
+        assert_noop!(self_result, Error::<Test>::SelfEndorsement);
+        assert_noop!(unknown_result, Error::<Test>::UnknownAuditor);
+        assert_ok!(second_result);
+        assert_noop!(too_many_result, Error::<Test>::TooManyEndorsers);
+        assert_noop!(not_endorsed_result, Error::<Test>::NotEndorsed);
+        assert_ok!(withdraw_result);
+        assert_eq!(AuditRepModule::endorsements(4).into_inner(), vec![(2, 20)]);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100);
+        assert_eq!(Balances::reserved_balance(2), 20);
+    });
+}
+
+#[test]
+fn endorsements_are_slashed_if_auditor_loses_challenge() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 5 is endorsed by accounts 1 and 2 and does not respond to a challenge of auditor 4
+        System::set_block_number(1);
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(1), 5, 50));
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(2), 5, 30));
+        assert_ok!(AuditRepModule::open_challenge(
+            RuntimeOrigin::signed(4),
+            5,
+            challenge(None)
+        ));
+
+        // When
+        let withdraw_result = AuditRepModule::withdraw_endorsement(RuntimeOrigin::signed(1), 5);
+        System::set_block_number(7);
+        let resolve_result = AuditRepModule::resolve_challenge(RuntimeOrigin::signed(3), 0);
+
+        // Then
+        // `EndorsementSlash` of every endorsement is paid to the winner
+        assert_noop!(withdraw_result, Error::<Test>::OpenChallenge);
+        assert_ok!(resolve_result);
+        assert_eq!(
+            AuditRepModule::endorsements(5).into_inner(),
+            vec![(1, 45), (2, 27)]
+        );
+        assert_eq!(Balances::reserved_balance(1), 45);
+        assert_eq!(Balances::free_balance(1), 50);
+        assert_eq!(Balances::free_balance(4), 908);
+        assert_ok!(AuditRepModule::withdraw_endorsement(
+            RuntimeOrigin::signed(1),
+            5
+        ));
+    });
+}
+
+#[test]
+fn review_fees_are_shared_with_endorsers() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 is endorsed by accounts 1 and 2, auditor 5 has no endorsers
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(1), 4, 30));
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(2), 4, 10));
+
+        // When
+        let endorsed_result = AuditRepModule::distribute_fee(&3, &4, 40);
+        let unendorsed_result = AuditRepModule::distribute_fee(&3, &5, 10);
+
+        // Then
+        // Half of the fee is split among the endorsers in proportion to their endorsements
+        assert_ok!(endorsed_result);
+        assert_ok!(unendorsed_result);
+        assert_eq!(Balances::free_balance(1), 85);
+        assert_eq!(Balances::free_balance(2), 95);
+        assert_eq!(Balances::free_balance(3), 50);
+        assert_eq!(Balances::free_balance(4), 920);
+        assert_eq!(Balances::free_balance(5), 910);
+    });
+}
+
+#[test]
+fn team_membership_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 has a score of 2000, auditor 7 a score of 1000 and account 8 is not an auditor
+        assert_ok!(AuditRepModule::create_team(RuntimeOrigin::signed(4), 3));
+
+        // When
+        let unknown_result = AuditRepModule::create_team(RuntimeOrigin::signed(8), 1);
+        let second_team_result = AuditRepModule::create_team(RuntimeOrigin::signed(4), 1);
+        let zero_shares_result = AuditRepModule::create_team(RuntimeOrigin::signed(5), 0);
+        let not_admin_result = AuditRepModule::invite_to_team(RuntimeOrigin::signed(5), 0, 6, 1);
+        assert_ok!(AuditRepModule::invite_to_team(
+            RuntimeOrigin::signed(4),
+            0,
+            7,
+            1
+        ));
+        let not_invited_result = AuditRepModule::accept_team_invite(RuntimeOrigin::signed(6), 0);
+        let accept_result = AuditRepModule::accept_team_invite(RuntimeOrigin::signed(7), 0);
+        let team_score = AuditRepModule::team_score(0);
+        let admin_leave_result = AuditRepModule::leave_team(RuntimeOrigin::signed(4));
+
+        // Then
+        // The team reputation is the average score of its members
+        assert_noop!(unknown_result, Error::<Test>::UnknownAuditor);
+        assert_noop!(second_team_result, Error::<Test>::AlreadyInTeam);
+        assert_noop!(zero_shares_result, Error::<Test>::ZeroShares);
+        assert_noop!(not_admin_result, Error::<Test>::NotTeamAdmin);
+        assert_noop!(not_invited_result, Error::<Test>::NoTeamInvite);
+        assert_ok!(accept_result);
+        assert_eq!(
+            AuditRepModule::teams(0).map(|team| team.members.into_inner()),
+            Some(vec![(4, 3), (7, 1)])
+        );
+        assert_eq!(team_score, Some(1500));
+        assert!(!AuditRepModule::team_has_privilege(
+            0,
+            Privilege::ClaimReview
+        ));
+        assert_noop!(admin_leave_result, Error::<Test>::TeamAdminCannotLeave);
+
+        assert_ok!(AuditRepModule::leave_team(RuntimeOrigin::signed(7)));
+        assert_eq!(AuditRepModule::team_score(0), Some(2000));
+        assert!(AuditRepModule::team_has_privilege(
+            0,
+            Privilege::ClaimReview
+        ));
+        assert_eq!(AuditRepModule::auditor_team(7), None);
+    });
+}
+
+#[test]
+fn team_members_cannot_withdraw() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 is the only member of a team and cancelled their account
+        assert_ok!(AuditRepModule::create_team(RuntimeOrigin::signed(4), 1));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(4)));
+        System::set_block_number(10);
+
+        // When
+        let withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(4));
+        let leave_result = AuditRepModule::leave_team(RuntimeOrigin::signed(4));
+
+        // Then
+        // The team is dissolved when its last member leaves
+        assert_noop!(withdraw_result, Error::<Test>::StillInTeam);
+        assert_ok!(leave_result);
+        assert_eq!(AuditRepModule::teams(0), None);
+        assert_ok!(AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(4)));
+    });
+}
+
+#[test]
+fn auditors_with_claimed_reviews_cannot_leave() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 claimed a review, auditor 5 cancelled their account before claiming one
+        assert_ok!(AuditRepModule::add_claimed_value(&4, 10));
+        assert_ok!(AuditRepModule::cancel_account(RuntimeOrigin::signed(5)));
+        assert_ok!(AuditRepModule::add_claimed_value(&5, 10));
+        System::set_block_number(10);
+
+        // When
+        let cancel_result = AuditRepModule::cancel_account(RuntimeOrigin::signed(4));
+        let withdraw_result = AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5));
+        AuditRepModule::release_claimed_value(&5, 10);
+
+        // Then
+        // The auditors can only leave once their reviews are released
+        assert_noop!(cancel_result, Error::<Test>::ClaimedReviews);
+        assert_noop!(withdraw_result, Error::<Test>::ClaimedReviews);
+        assert_ok!(AuditRepModule::withdraw_unbonded(RuntimeOrigin::signed(5)));
+    });
+}
+
+#[test]
+fn team_fees_are_split_by_shares() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditor 4 holds three shares of the team, auditor 5 one share and is endorsed by account 1
+        assert_ok!(AuditRepModule::create_team(RuntimeOrigin::signed(4), 3));
+        assert_ok!(AuditRepModule::invite_to_team(
+            RuntimeOrigin::signed(4),
+            0,
+            5,
+            1
+        ));
+        assert_ok!(AuditRepModule::accept_team_invite(
+            RuntimeOrigin::signed(5),
+            0
+        ));
+        assert_ok!(AuditRepModule::endorse(RuntimeOrigin::signed(1), 5, 10));
+
+        // When
+        let distribute_result = AuditRepModule::distribute_team_fee(&2, 0, 40);
+
+        // Then
+        // The share of auditor 5 is shared with their endorser
+        assert_ok!(distribute_result);
+        assert_eq!(Balances::free_balance(2), 60);
+        assert_eq!(Balances::free_balance(4), 930);
+        assert_eq!(Balances::free_balance(5), 905);
+        assert_eq!(Balances::free_balance(1), 95);
+    });
+}
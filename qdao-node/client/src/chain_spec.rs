@@ -1,7 +1,7 @@
 use qdao_audit_pallet::{pallet::GenesisConfig as AuditConfig, AuditorData};
 use qdao_runtime::{
-    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-    SystemConfig, WASM_BINARY,
+    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, MaxProfileLocatorLength,
+    MaxSpecializations, RequiredApprovals, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -143,10 +143,18 @@ fn testnet_genesis(
     endowed_accounts: Vec<AccountId>,
     _enable_println: bool,
 ) -> GenesisConfig {
-    let auditor_data = AuditorData::<H256, AccountId> {
+    let auditor_data = AuditorData::<
+        H256,
+        AccountId,
+        RequiredApprovals,
+        MaxSpecializations,
+        MaxProfileLocatorLength,
+    > {
         score: Some(2000),
         profile_hash: H256::repeat_byte(1),
-        approved_by: BoundedVec::with_bounded_capacity(3),
+        approved_by: BoundedVec::default(),
+        specializations: BoundedVec::default(),
+        profile_locator: None,
     };
     GenesisConfig {
         system: SystemConfig {
@@ -7,12 +7,20 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use qdao_runtime::{opaque::Block, AccountId, Balance, Index};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{CallError, ErrorObject},
+    RpcModule,
+};
+use qdao_audit_pallet::{AuditorStatus, Domain, LeaderboardEntry};
+use qdao_audit_runtime_api::AuditApi as AuditRuntimeApi;
+use qdao_runtime::{opaque::Block, AccountId, Balance, Hash, Index};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::generic::BlockId;
 
 pub use sc_rpc_api::DenyUnsafe;
 
@@ -26,6 +34,62 @@ pub struct FullDeps<C, P> {
     pub deny_unsafe: DenyUnsafe,
 }
 
+/// Auditor queries of `qdao-audit-pallet`.
+#[rpc(server)]
+pub trait AuditApi {
+    /// Returns auditors sorted by score in descending order, optionally filtered by approval status
+    /// and specialization. Pages are selected with `offset` and `limit`.
+    #[method(name = "audit_leaderboard")]
+    fn leaderboard(
+        &self,
+        status: AuditorStatus,
+        specialization: Option<Domain>,
+        offset: u32,
+        limit: u32,
+        at: Option<Hash>,
+    ) -> RpcResult<Vec<LeaderboardEntry<AccountId>>>;
+}
+
+/// Implementation of the `AuditApi` RPC, which calls into the `AuditApi` runtime API.
+pub struct Audit<C> {
+    client: Arc<C>,
+}
+
+impl<C> Audit<C> {
+    /// Create a new instance of the `AuditApi` RPC.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+impl<C> AuditApiServer for Audit<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: AuditRuntimeApi<Block, AccountId>,
+{
+    fn leaderboard(
+        &self,
+        status: AuditorStatus,
+        specialization: Option<Domain>,
+        offset: u32,
+        limit: u32,
+        at: Option<Hash>,
+    ) -> RpcResult<Vec<LeaderboardEntry<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.leaderboard(&at, status, specialization, offset, limit)
+            .map_err(|e| {
+                CallError::Custom(ErrorObject::owned(
+                    1,
+                    "Unable to query the auditor leaderboard.",
+                    Some(e.to_string()),
+                ))
+                .into()
+            })
+    }
+}
+
 /// Instantiate all full RPC extensions.
 pub fn create_full<C, P>(
     deps: FullDeps<C, P>,
@@ -37,6 +101,7 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BlockBuilder<Block>,
+    C::Api: AuditRuntimeApi<Block, AccountId>,
     P: TransactionPool + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
@@ -50,7 +115,8 @@ where
     } = deps;
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-    module.merge(TransactionPayment::new(client).into_rpc())?;
+    module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(Audit::new(client).into_rpc())?;
 
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
@@ -9,7 +9,10 @@ use frame_support::{
 };
 use frame_system::Config as SystemConfig;
 pub use pallet::*;
-use qdao_audit_pallet::Game;
+use qdao_audit_pallet::{
+    ChallengeContext, Domain, FeeDistributor, Game, Privilege, PrivilegeProvider, Severity,
+    StakeLedger, TeamId, TeamProvider,
+};
 use scale_info::TypeInfo;
 use sp_std::prelude::*;
 
@@ -22,6 +25,8 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+
 type DepositBalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
 
@@ -58,6 +63,9 @@ pub mod pallet {
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
 
+    /// The current storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -71,10 +79,23 @@ pub mod pallet {
         type Currency: ReservableCurrency<Self::AccountId>;
 
         type Game: qdao_audit_pallet::pallet::Game<Self>;
+
+        /// Score-gated privileges of auditors
+        type Privileges: PrivilegeProvider<Self::AccountId>;
+
+        /// Ledger of the review value claimed by auditors, which has to be covered by their stake
+        type Stakes: StakeLedger<Self::AccountId, DepositBalanceOf<Self>>;
+
+        /// Pays review fees to auditors and their endorsers
+        type Fees: FeeDistributor<Self::AccountId, DepositBalanceOf<Self>>;
+
+        /// Audit teams which can claim reviews together
+        type Teams: TeamProvider<Self::AccountId, DepositBalanceOf<Self>>;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
@@ -82,6 +103,18 @@ pub mod pallet {
     ///
     pub type ReviewRecord<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, ReviewData<T>>;
 
+    #[pallet::storage]
+    /// Auditors which claimed a review for manual processing
+    pub type ClaimedBy<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, T::AccountId>;
+
+    #[pallet::storage]
+    /// Teams which claimed a review, the review is processed by the claiming member on behalf of the team
+    pub type ClaimedByTeam<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, TeamId>;
+
+    #[pallet::storage]
+    /// Auditors which completed a claimed review and were paid the review deposit
+    pub type CompletedBy<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, T::AccountId>;
+
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/v3/runtime/events-and-errors
     #[pallet::event]
@@ -98,6 +131,22 @@ pub mod pallet {
             ret_hash: T::Hash,
             ret_result: Vec<u8>,
         },
+        /// An auditor claimed a review for manual processing
+        ReviewClaimed { who: T::AccountId, hash: T::Hash },
+        /// A team member claimed a review on behalf of their team
+        ReviewClaimedByTeam {
+            who: T::AccountId,
+            team: TeamId,
+            hash: T::Hash,
+        },
+        /// An auditor released a claimed review
+        ReviewUnclaimed { who: T::AccountId, hash: T::Hash },
+        /// An auditor completed a claimed review and was paid the review deposit
+        ReviewCompleted {
+            who: T::AccountId,
+            hash: T::Hash,
+            result: u32,
+        },
     }
 
     // Errors inform users that something went wrong.
@@ -111,6 +160,18 @@ pub mod pallet {
         UrlTooLong,
         /// Request hash collision
         DuplicateEntry,
+        /// The auditor's score is too low for this action
+        NotPrivileged,
+        /// The review was already claimed
+        AlreadyClaimed,
+        /// The review was not claimed by the caller
+        NotClaimant,
+        /// The review was already completed
+        AlreadyCompleted,
+        /// The auditor is not member of a team
+        NotTeamMember,
+        /// The review was not completed yet
+        NotCompleted,
     }
 
     // Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -179,17 +240,139 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Record automated request processing results
-        #[pallet::weight(Weight::from_ref_time(1000) + T::DbWeight::get().writes(1))]
+        /// Claim a review for manual processing, requires the `ClaimReview` privilege
+        #[pallet::weight(Weight::from_ref_time(1000) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn claim_review(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                T::Privileges::has_privilege(&sender, Privilege::ClaimReview),
+                Error::<T>::NotPrivileged
+            );
+            let review = ReviewRecord::<T>::get(hash).ok_or(Error::<T>::NoneValue)?;
+            ensure!(
+                !CompletedBy::<T>::contains_key(hash),
+                Error::<T>::AlreadyCompleted
+            );
+            ensure!(
+                !ClaimedBy::<T>::contains_key(hash),
+                Error::<T>::AlreadyClaimed
+            );
+
+            T::Stakes::add_claimed_value(&sender, review.deposit)?;
+            ClaimedBy::<T>::insert(hash, sender.clone());
+
+            Self::deposit_event(Event::ReviewClaimed { who: sender, hash });
+            Ok(())
+        }
+
+        /// Claim a review on behalf of the caller's team, requires the `ClaimReview` privilege for the team's reputation.
+        /// The review fee is split among the team members according to their shares.
+        #[pallet::weight(Weight::from_ref_time(1000) + T::DbWeight::get().reads_writes(5, 2))]
+        pub fn claim_review_for_team(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let team = T::Teams::team_of(&sender).ok_or(Error::<T>::NotTeamMember)?;
+            ensure!(
+                T::Teams::team_has_privilege(team, Privilege::ClaimReview),
+                Error::<T>::NotPrivileged
+            );
+            let review = ReviewRecord::<T>::get(hash).ok_or(Error::<T>::NoneValue)?;
+            ensure!(
+                !CompletedBy::<T>::contains_key(hash),
+                Error::<T>::AlreadyCompleted
+            );
+            ensure!(
+                !ClaimedBy::<T>::contains_key(hash),
+                Error::<T>::AlreadyClaimed
+            );
+
+            T::Stakes::add_claimed_value(&sender, review.deposit)?;
+            ClaimedBy::<T>::insert(hash, sender.clone());
+            ClaimedByTeam::<T>::insert(hash, team);
+
+            Self::deposit_event(Event::ReviewClaimedByTeam {
+                who: sender,
+                team,
+                hash,
+            });
+            Ok(())
+        }
+
+        /// Release a claimed review, so that it can be claimed by another auditor
+        #[pallet::weight(Weight::from_ref_time(1000) + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn unclaim_review(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                ClaimedBy::<T>::get(hash).as_ref() == Some(&sender),
+                Error::<T>::NotClaimant
+            );
+            let review = ReviewRecord::<T>::get(hash).ok_or(Error::<T>::NoneValue)?;
+
+            T::Stakes::release_claimed_value(&sender, review.deposit);
+            ClaimedBy::<T>::remove(hash);
+            ClaimedByTeam::<T>::remove(hash);
+
+            Self::deposit_event(Event::ReviewUnclaimed { who: sender, hash });
+            Ok(())
+        }
+
+        /// Complete a claimed review. The deposit of the requestor is paid to the auditor and their endorsers, or to the
+        /// members of the team if the review was claimed on behalf of a team.
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(5, 5))]
+        pub fn complete_review(origin: OriginFor<T>, hash: T::Hash, result: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(
+                ClaimedBy::<T>::get(hash).as_ref() == Some(&sender),
+                Error::<T>::NotClaimant
+            );
+            let mut review = ReviewRecord::<T>::get(hash).ok_or(Error::<T>::NoneValue)?;
+
+            T::Currency::unreserve(&review.requestor, review.deposit);
+            match ClaimedByTeam::<T>::take(hash) {
+                Some(team) => {
+                    T::Teams::distribute_team_fee(&review.requestor, team, review.deposit)?
+                }
+                None => T::Fees::distribute_fee(&review.requestor, &sender, review.deposit)?,
+            }
+            T::Stakes::release_claimed_value(&sender, review.deposit);
+
+            review.result = ReviewResult { result };
+            ReviewRecord::<T>::insert(hash, review);
+            ClaimedBy::<T>::remove(hash);
+            CompletedBy::<T>::insert(hash, sender.clone());
+
+            Self::deposit_event(Event::ReviewCompleted {
+                who: sender,
+                hash,
+                result,
+            });
+            Ok(())
+        }
+
+        /// Challenge the auditor who completed a review because of a missed finding of the given severity in the given
+        /// domain. The challenge is resolved by the challenge protocol of the audit pallet.
+        #[pallet::weight(Weight::from_ref_time(10_000) + T::DbWeight::get().reads_writes(6, 4))]
         pub fn challenge_report(
             origin: OriginFor<T>,
             challenged_hash: T::Hash,
             _result: Vec<u8>,
+            severity: Severity,
+            domain: Option<Domain>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
-            let review = ReviewRecord::<T>::get(challenged_hash).ok_or(Error::<T>::NoneValue)?;
-            // TODO Not all challenges should win, right? :smile:
-            T::Game::apply_result(sender, review.requestor, qdao_audit_pallet::Winner::Player0)?;
+            let auditor = CompletedBy::<T>::get(challenged_hash).ok_or(Error::<T>::NotCompleted)?;
+            T::Game::challenge(
+                sender,
+                auditor,
+                ChallengeContext {
+                    review_hash: Some(challenged_hash),
+                    severity,
+                    domain,
+                },
+            )?;
             Ok(())
         }
     }
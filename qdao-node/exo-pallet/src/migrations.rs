@@ -0,0 +1,70 @@
+//! Storage migrations for `qdao-exo-pallet`
+//!
+//! Every migration module keeps a copy of `ReviewData` in the layout of its storage version once the layout changes,
+//! so that each step can decode the entries written by the previous one.
+
+use super::*;
+use frame_support::{
+    log,
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+/// Migration to storage version 1, the first storage version of the pallet
+pub mod v1 {
+    use super::*;
+
+    /// Stores storage version 1. The layout of `ReviewData` did not change, so existing review records are only
+    /// counted, but later migrations can rely on the version of the stored entries.
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 0 {
+                log::info!(
+                    target: "runtime::qdao-exo",
+                    "Skipping migration to v1, on-chain storage version is {:?}",
+                    on_chain_version
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let reviews = <ReviewRecord<T>>::iter_keys().count() as u64;
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            log::info!(
+                target: "runtime::qdao-exo",
+                "Migrated {} review records to v1",
+                reviews
+            );
+
+            T::DbWeight::get().reads_writes(reviews + 1, 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            Ok((<ReviewRecord<T>>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            let reviews = u32::decode(&mut &state[..]).map_err(|_| "Invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 1,
+                "Storage version was not updated"
+            );
+            ensure!(
+                <ReviewRecord<T>>::iter_keys().count() as u32 == reviews,
+                "Number of review records changed during the migration"
+            );
+            ensure!(
+                <ReviewRecord<T>>::iter_keys().all(|hash| <ReviewRecord<T>>::try_get(hash).is_ok()),
+                "Review record does not decode in the current layout"
+            );
+            Ok(())
+        }
+    }
+}
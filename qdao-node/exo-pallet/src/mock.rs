@@ -28,6 +28,11 @@ construct_runtime!(
 
 parameter_types! {
     pub const ExistentialDeposit: u64 = 1;
+    pub static PrivilegedAuditors: Vec<u64> = vec![];
+    pub static MaxClaimedValue: u64 = u64::MAX;
+    pub static ClaimedValue: u64 = 0;
+    pub static TeamMembers: Vec<u64> = vec![];
+    pub static OpenedChallenges: Vec<(u64, u64, Option<H256>)> = vec![];
 }
 
 impl system::Config for Test {
@@ -74,6 +79,10 @@ impl qdao_exo_pallet::Config for Test {
     type Balance = u32;
     type Currency = Balances;
     type Game = Self;
+    type Privileges = Self;
+    type Stakes = Self;
+    type Fees = Self;
+    type Teams = Self;
 }
 
 impl qdao_audit_pallet::Game<Test> for Test {
@@ -81,9 +90,92 @@ impl qdao_audit_pallet::Game<Test> for Test {
         _player0: <Test as system::Config>::AccountId,
         _player1: <Test as system::Config>::AccountId,
         _winner: qdao_audit_pallet::Winner,
+        _context: qdao_audit_pallet::ChallengeContext<<Test as system::Config>::Hash>,
     ) -> frame_support::pallet_prelude::DispatchResult {
         Ok(())
     }
+
+    /// Opened challenges are recorded in `OpenedChallenges`
+    fn challenge(
+        challenger: u64,
+        defendant: u64,
+        context: qdao_audit_pallet::ChallengeContext<H256>,
+    ) -> frame_support::pallet_prelude::DispatchResult {
+        let mut challenges = OpenedChallenges::get();
+        challenges.push((challenger, defendant, context.review_hash));
+        OpenedChallenges::set(challenges);
+        Ok(())
+    }
+}
+
+/// Auditors in `PrivilegedAuditors` have all privileges
+impl qdao_audit_pallet::PrivilegeProvider<u64> for Test {
+    fn has_privilege(who: &u64, _privilege: qdao_audit_pallet::Privilege) -> bool {
+        PrivilegedAuditors::get().contains(who)
+    }
+}
+
+/// Claimed review values are summed up in `ClaimedValue`, which must not exceed `MaxClaimedValue`
+impl qdao_audit_pallet::StakeLedger<u64, u64> for Test {
+    fn add_claimed_value(_who: &u64, value: u64) -> frame_support::pallet_prelude::DispatchResult {
+        let claimed = ClaimedValue::get() + value;
+        frame_support::ensure!(
+            claimed <= MaxClaimedValue::get(),
+            sp_runtime::DispatchError::Other("InsufficientStake")
+        );
+        ClaimedValue::set(claimed);
+        Ok(())
+    }
+
+    fn release_claimed_value(_who: &u64, value: u64) {
+        ClaimedValue::set(ClaimedValue::get() - value);
+    }
+}
+
+/// Review fees are paid to the auditor only
+impl qdao_audit_pallet::FeeDistributor<u64, u64> for Test {
+    fn distribute_fee(
+        payer: &u64,
+        auditor: &u64,
+        fee: u64,
+    ) -> frame_support::pallet_prelude::DispatchResult {
+        <Balances as frame_support::traits::Currency<u64>>::transfer(
+            payer,
+            auditor,
+            fee,
+            frame_support::traits::ExistenceRequirement::AllowDeath,
+        )
+    }
+}
+
+/// The accounts in `TeamMembers` form team 0, which has all privileges and splits fees evenly
+impl qdao_audit_pallet::TeamProvider<u64, u64> for Test {
+    fn team_of(who: &u64) -> Option<qdao_audit_pallet::TeamId> {
+        TeamMembers::get().contains(who).then_some(0)
+    }
+
+    fn team_has_privilege(
+        team: qdao_audit_pallet::TeamId,
+        _privilege: qdao_audit_pallet::Privilege,
+    ) -> bool {
+        team == 0
+    }
+
+    fn distribute_team_fee(
+        payer: &u64,
+        _team: qdao_audit_pallet::TeamId,
+        fee: u64,
+    ) -> frame_support::pallet_prelude::DispatchResult {
+        let members = TeamMembers::get();
+        for member in members.iter() {
+            <Test as qdao_audit_pallet::FeeDistributor<u64, u64>>::distribute_fee(
+                payer,
+                member,
+                fee / members.len() as u64,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 // Build genesis storage according to the mock runtime.
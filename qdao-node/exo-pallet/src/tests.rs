@@ -1,5 +1,14 @@
 use crate::mock::*;
-use frame_support::assert_ok;
+use crate::{
+    migrations::v1::MigrateToV1, ClaimedBy, ClaimedByTeam, CompletedBy, Error, ReviewRecord,
+};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::unhashed,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use qdao_audit_pallet::Severity;
 use sp_core::H256;
 
 #[test]
@@ -13,3 +22,222 @@ fn dispatch_review_request() {
         ));
     });
 }
+
+#[test]
+fn claim_review_requires_privilege() {
+    new_test_ext().execute_with(|| {
+        PrivilegedAuditors::set(vec![20, 30]);
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(1),
+            Vec::new(),
+            H256::zero(),
+            1
+        ));
+
+        assert_noop!(
+            Exosys::claim_review(RuntimeOrigin::signed(10), H256::zero()),
+            Error::<Test>::NotPrivileged
+        );
+        assert_noop!(
+            Exosys::claim_review(RuntimeOrigin::signed(20), H256::repeat_byte(1)),
+            Error::<Test>::NoneValue
+        );
+        assert_ok!(Exosys::claim_review(
+            RuntimeOrigin::signed(20),
+            H256::zero()
+        ));
+        assert_noop!(
+            Exosys::claim_review(RuntimeOrigin::signed(30), H256::zero()),
+            Error::<Test>::AlreadyClaimed
+        );
+        assert_eq!(ClaimedBy::<Test>::get(H256::zero()), Some(20));
+    });
+}
+
+#[test]
+fn claimed_review_value_is_tracked() {
+    new_test_ext().execute_with(|| {
+        // Given
+        PrivilegedAuditors::set(vec![20, 30]);
+        MaxClaimedValue::set(5);
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(1),
+            Vec::new(),
+            H256::zero(),
+            3
+        ));
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(2),
+            Vec::new(),
+            H256::repeat_byte(1),
+            4
+        ));
+
+        // When
+        assert_ok!(Exosys::claim_review(
+            RuntimeOrigin::signed(20),
+            H256::zero()
+        ));
+
+        // Then
+        assert_eq!(ClaimedValue::get(), 3);
+        assert_noop!(
+            Exosys::claim_review(RuntimeOrigin::signed(20), H256::repeat_byte(1)),
+            sp_runtime::DispatchError::Other("InsufficientStake")
+        );
+        assert_noop!(
+            Exosys::unclaim_review(RuntimeOrigin::signed(30), H256::zero()),
+            Error::<Test>::NotClaimant
+        );
+
+        assert_ok!(Exosys::unclaim_review(
+            RuntimeOrigin::signed(20),
+            H256::zero()
+        ));
+        assert_eq!(ClaimedValue::get(), 0);
+        assert_eq!(ClaimedBy::<Test>::get(H256::zero()), None);
+        assert_ok!(Exosys::claim_review(
+            RuntimeOrigin::signed(20),
+            H256::repeat_byte(1)
+        ));
+        assert_eq!(ClaimedValue::get(), 4);
+    });
+}
+
+#[test]
+fn complete_review_pays_claimant() {
+    new_test_ext().execute_with(|| {
+        // Given
+        PrivilegedAuditors::set(vec![20, 30]);
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(1),
+            Vec::new(),
+            H256::zero(),
+            5
+        ));
+        assert_ok!(Exosys::claim_review(
+            RuntimeOrigin::signed(20),
+            H256::zero()
+        ));
+
+        // When
+        let unclaimed_result = Exosys::complete_review(RuntimeOrigin::signed(30), H256::zero(), 1);
+        let complete_result = Exosys::complete_review(RuntimeOrigin::signed(20), H256::zero(), 1);
+        let claim_again_result = Exosys::claim_review(RuntimeOrigin::signed(30), H256::zero());
+
+        // Then
+        // The deposit of the requestor is paid to the auditor
+        assert_noop!(unclaimed_result, Error::<Test>::NotClaimant);
+        assert_ok!(complete_result);
+        assert_noop!(claim_again_result, Error::<Test>::AlreadyCompleted);
+        assert_eq!(Balances::free_balance(1), 5);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(20), 105);
+        assert_eq!(ClaimedValue::get(), 0);
+        assert_eq!(ClaimedBy::<Test>::get(H256::zero()), None);
+        assert_eq!(CompletedBy::<Test>::get(H256::zero()), Some(20));
+        assert!(ReviewRecord::<Test>::get(H256::zero()).is_some());
+    });
+}
+
+#[test]
+fn challenge_report_challenges_the_auditor_of_the_review() {
+    new_test_ext().execute_with(|| {
+        // Given
+        PrivilegedAuditors::set(vec![20]);
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(1),
+            Vec::new(),
+            H256::zero(),
+            5
+        ));
+        assert_ok!(Exosys::claim_review(
+            RuntimeOrigin::signed(20),
+            H256::zero()
+        ));
+        let challenge = |hash| {
+            Exosys::challenge_report(
+                RuntimeOrigin::signed(30),
+                hash,
+                Vec::new(),
+                Severity::High,
+                None,
+            )
+        };
+
+        // When
+        let open_result = challenge(H256::zero());
+        assert_ok!(Exosys::complete_review(
+            RuntimeOrigin::signed(20),
+            H256::zero(),
+            1
+        ));
+        let completed_result = challenge(H256::zero());
+
+        // Then
+        // Only completed reviews can be challenged, the challenge is opened against their auditor
+        assert_noop!(open_result, Error::<Test>::NotCompleted);
+        assert_ok!(completed_result);
+        assert_eq!(OpenedChallenges::get(), vec![(30, 20, Some(H256::zero()))]);
+    });
+}
+
+#[test]
+fn team_review_fees_are_split() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // Auditors 20 and 30 form a team, auditor 10 is not member of a team
+        TeamMembers::set(vec![20, 30]);
+        assert_ok!(Exosys::tool_exec_req(
+            RuntimeOrigin::signed(1),
+            Vec::new(),
+            H256::zero(),
+            6
+        ));
+
+        // When
+        let no_team_result = Exosys::claim_review_for_team(RuntimeOrigin::signed(10), H256::zero());
+        let claim_result = Exosys::claim_review_for_team(RuntimeOrigin::signed(20), H256::zero());
+        let complete_result = Exosys::complete_review(RuntimeOrigin::signed(20), H256::zero(), 1);
+
+        // Then
+        // The deposit is split among the team members
+        assert_noop!(no_team_result, Error::<Test>::NotTeamMember);
+        assert_ok!(claim_result);
+        assert_ok!(complete_result);
+        assert_eq!(ClaimedByTeam::<Test>::get(H256::zero()), None);
+        assert_eq!(CompletedBy::<Test>::get(H256::zero()), Some(20));
+        assert_eq!(Balances::free_balance(20), 103);
+        assert_eq!(Balances::free_balance(30), 103);
+        assert_eq!(Balances::free_balance(1), 4);
+    });
+}
+
+#[test]
+fn migration_to_v1_works() {
+    new_test_ext().execute_with(|| {
+        // Given
+        // A review record in the layout of storage version 0: deposit, requestor, hash, url and result
+        StorageVersion::new(0).put::<Exosys>();
+        let hash = H256::repeat_byte(1);
+        unhashed::put_raw(
+            &ReviewRecord::<Test>::hashed_key_for(hash),
+            &(5u64, 1u64, hash, b"https://example.com".to_vec(), 0u32).encode(),
+        );
+
+        // When
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV1::<Test>::pre_upgrade().expect("Pre-upgrade checks failed");
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV1::<Test>::post_upgrade(state).expect("Post-upgrade checks failed");
+
+        // Then
+        // The record decodes in the current layout
+        let review = ReviewRecord::<Test>::get(hash).expect("Review record not available");
+        assert_eq!(review.deposit, 5);
+        assert_eq!(review.requestor, 1);
+        assert_eq!(review.url.into_inner(), b"https://example.com".to_vec());
+        assert_eq!(Exosys::on_chain_storage_version(), 1);
+    });
+}
@@ -6,7 +6,10 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
-use frame_system::limits::{BlockLength, BlockWeights};
+use frame_system::{
+    limits::{BlockLength, BlockWeights},
+    EnsureRoot,
+};
 use pallet_grandpa::{
     fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -104,10 +107,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     state_version: 1,
 };
 
@@ -171,6 +174,35 @@ parameter_types! {
     pub const MinAuditorStake: Balance = 100;
     pub const InitialAuditorScore: u32 = 1000;
     pub const MinimalApproverScore: u32 = 2000;
+    pub const UnbondingPeriod: BlockNumber = 7 * DAYS;
+    pub const RequiredApprovals: u32 = 3;
+    pub const ScoreWeightedApprovals: bool = false;
+    pub const ApplicationExpiry: BlockNumber = 30 * DAYS;
+    pub const MaxSpecializations: u32 = 3;
+    pub const MaxProfileLocatorLength: u32 = 256;
+    pub const RequireIdentity: bool = false;
+    pub const PairCooldown: BlockNumber = DAYS;
+    pub const AuditorCooldown: BlockNumber = 10 * MINUTES;
+    pub const RatingPeriod: BlockNumber = 7 * DAYS;
+    pub const MaxGamesPerPeriod: u32 = 20;
+    pub const ChallengeBond: Balance = 100;
+    pub const ResponsePeriod: BlockNumber = 3 * DAYS;
+    pub const VotingPeriod: BlockNumber = 7 * DAYS;
+    pub const MaxJurors: u32 = 15;
+    pub const StakePerPrivilege: Balance = 50;
+    pub const ClaimedValueRatio: Perbill = Perbill::from_percent(10);
+    pub const MaxEndorsers: u32 = 64;
+    pub const MinEndorsement: Balance = 10;
+    pub const EndorserFeeShare: Perbill = Perbill::from_percent(20);
+    pub const EndorsementSlash: Perbill = Perbill::from_percent(10);
+    pub const MaxTeamMembers: u32 = 50;
+    pub const MaxAuditorCleanup: u32 = 100;
+    pub const BasicDeposit: Balance = 1000;
+    pub const FieldDeposit: Balance = 250;
+    pub const SubAccountDeposit: Balance = 200;
+    pub const MaxSubAccounts: u32 = 100;
+    pub const MaxAdditionalFields: u32 = 100;
+    pub const MaxRegistrars: u32 = 20;
 }
 
 // Configure FRAME pallets to include in runtime.
@@ -293,6 +325,51 @@ impl pallet_sudo::Config for Runtime {
     type RuntimeCall = RuntimeCall;
 }
 
+impl pallet_identity::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type BasicDeposit = BasicDeposit;
+    type FieldDeposit = FieldDeposit;
+    type SubAccountDeposit = SubAccountDeposit;
+    type MaxSubAccounts = MaxSubAccounts;
+    type MaxAdditionalFields = MaxAdditionalFields;
+    type MaxRegistrars = MaxRegistrars;
+    type Slashed = ();
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type RegistrarOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+/// Provides the judgements of `pallet_identity` registrars to the qdao-audit-pallet.
+/// An identity is rejected if any registrar judged it as low quality or erroneous.
+pub struct IdentityJudgement;
+
+impl qdao_audit_pallet::IdentityVerifier<AccountId> for IdentityJudgement {
+    fn identity_status(who: &AccountId) -> qdao_audit_pallet::IdentityStatus {
+        use pallet_identity::Judgement;
+        use qdao_audit_pallet::IdentityStatus;
+
+        let registration = match Identity::identity(who) {
+            Some(registration) => registration,
+            None => return IdentityStatus::NotSet,
+        };
+        let judgements = || {
+            registration
+                .judgements
+                .iter()
+                .map(|(_, judgement)| judgement)
+        };
+
+        if judgements().any(|j| matches!(j, Judgement::LowQuality | Judgement::Erroneous)) {
+            IdentityStatus::Rejected
+        } else if judgements().any(|j| matches!(j, Judgement::Reasonable | Judgement::KnownGood)) {
+            IdentityStatus::Verified
+        } else {
+            IdentityStatus::Unjudged
+        }
+    }
+}
+
 /// Configure the qdao-audit-pallet.
 impl qdao_audit_pallet::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
@@ -301,6 +378,31 @@ impl qdao_audit_pallet::Config for Runtime {
     type MinAuditorStake = MinAuditorStake;
     type InitialAuditorScore = InitialAuditorScore;
     type MinimalApproverScore = MinimalApproverScore;
+    type UnbondingPeriod = UnbondingPeriod;
+    type RequiredApprovals = RequiredApprovals;
+    type ScoreWeightedApprovals = ScoreWeightedApprovals;
+    type ApplicationExpiry = ApplicationExpiry;
+    type MaxSpecializations = MaxSpecializations;
+    type MaxProfileLocatorLength = MaxProfileLocatorLength;
+    type IdentityVerifier = IdentityJudgement;
+    type RequireIdentity = RequireIdentity;
+    type PairCooldown = PairCooldown;
+    type AuditorCooldown = AuditorCooldown;
+    type RatingPeriod = RatingPeriod;
+    type MaxGamesPerPeriod = MaxGamesPerPeriod;
+    type ChallengeBond = ChallengeBond;
+    type ResponsePeriod = ResponsePeriod;
+    type VotingPeriod = VotingPeriod;
+    type MaxJurors = MaxJurors;
+    type OracleOrigin = EnsureRoot<AccountId>;
+    type StakePerPrivilege = StakePerPrivilege;
+    type ClaimedValueRatio = ClaimedValueRatio;
+    type MaxEndorsers = MaxEndorsers;
+    type MinEndorsement = MinEndorsement;
+    type EndorserFeeShare = EndorserFeeShare;
+    type EndorsementSlash = EndorsementSlash;
+    type MaxTeamMembers = MaxTeamMembers;
+    type MaxAuditorCleanup = MaxAuditorCleanup;
 }
 
 /// Configure the qdao-exo-pallet.
@@ -309,6 +411,10 @@ impl qdao_exo_pallet::Config for Runtime {
     type Balance = Balance;
     type Currency = Balances;
     type Game = qdao_audit_pallet::Pallet<Runtime>;
+    type Privileges = qdao_audit_pallet::Pallet<Runtime>;
+    type Stakes = qdao_audit_pallet::Pallet<Runtime>;
+    type Fees = qdao_audit_pallet::Pallet<Runtime>;
+    type Teams = qdao_audit_pallet::Pallet<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -329,6 +435,7 @@ construct_runtime!(
         // Include the custom logic from the pallet-template in the runtime.
         ExoSys: qdao_exo_pallet,
         AuditModule: qdao_audit_pallet,
+        Identity: pallet_identity,
     }
 );
 
@@ -354,6 +461,15 @@ pub type UncheckedExtrinsic =
     generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Storage migrations which are executed on runtime upgrade.
+type Migrations = (
+    qdao_audit_pallet::migrations::v1::MigrateToV1<Runtime>,
+    qdao_audit_pallet::migrations::v2::MigrateToV2<Runtime>,
+    qdao_audit_pallet::migrations::v3::MigrateToV3<Runtime>,
+    qdao_audit_pallet::migrations::v4::MigrateToV4<Runtime>,
+    qdao_audit_pallet::migrations::v5::MigrateToV5<Runtime>,
+    qdao_exo_pallet::migrations::v1::MigrateToV1<Runtime>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -361,6 +477,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -509,6 +626,17 @@ impl_runtime_apis! {
         }
     }
 
+    impl qdao_audit_runtime_api::AuditApi<Block, AccountId> for Runtime {
+        fn leaderboard(
+            status: qdao_audit_pallet::AuditorStatus,
+            specialization: Option<qdao_audit_pallet::Domain>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<qdao_audit_pallet::LeaderboardEntry<AccountId>> {
+            AuditModule::leaderboard(status, specialization, offset, limit)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn benchmark_metadata(extra: bool) -> (